@@ -7,78 +7,173 @@
 */
 
 use soroban_kit::{
-    commit, fsm, fsm::StateMachine, reveal, soroban_tools, state_machine, storage,
-    TransitionHandler,
+    fsm, fsm::StateMachine, soroban_tools, state_machine, storage, TransitionHandler,
 };
-use soroban_sdk::{symbol_short, token, Address, Bytes, BytesN, Env, Symbol};
+use soroban_sdk::{symbol_short, token, vec, xdr::ToXdr, Address, Bytes, BytesN, Env, Vec};
 
-use crate::types::{AdminData, AuctionData, AuctionPhase, AuctionRegion, BidData, DataKey};
+use crate::events::{
+    AuctionCancelled, AuctionExtended, AuctionSettled, AuctionStarted, BidPlaced, BidRefunded,
+    BidSealed, DepositsForfeited, Payment, ReserveRevealed, Settlement, AUCTION, BID,
+    EVENTS_VERSION,
+};
+use crate::index;
+use crate::royalty::pay_royalty;
+use crate::types::{
+    AdminData, AuctionData, AuctionKind, AuctionOutcome, AuctionPhase, AuctionRegion,
+    AuctionSettings, BidData, CancellationPolicy, DataKey, OutcomeKind, Role, SoftClosePolicy,
+    MAX_BPS, RESERVE_REVEAL_TIME,
+};
 
 use super::behavior_ascending_price::*;
 use super::behavior_descending_price::*;
-
-// Event topics.
-const AUCTION: Symbol = symbol_short!("AUCTION");
-const BID: Symbol = symbol_short!("BID");
+use super::behavior_second_price::*;
+use super::behavior_uniform_price::*;
 
 pub mod ledger_times {
     // Assuming 6 seconds average time per ledger.
-    pub const LEDGERS_PER_MINUTE: u64 = 10;
+    pub const SECONDS_PER_LEDGER: u64 = 6;
+    pub const LEDGERS_PER_MINUTE: u64 = 60 / SECONDS_PER_LEDGER;
     pub const LEDGERS_PER_HOUR: u64 = LEDGERS_PER_MINUTE * 60;
     pub const LEDGERS_PER_DAY: u64 = LEDGERS_PER_HOUR * 24;
     pub const LEDGERS_PER_YEAR: u64 = LEDGERS_PER_DAY * 365;
+
+    // Number of ledgers covering `seconds` (i.e., seconds / 6 rounded up), capped to a year.
+    pub const fn convert_seconds_to_ledgers(seconds: u64) -> u32 {
+        let ledgers = seconds.div_ceil(SECONDS_PER_LEDGER);
+        if ledgers < LEDGERS_PER_YEAR {
+            ledgers as u32
+        } else {
+            LEDGERS_PER_YEAR as u32
+        }
+    }
+}
+
+// Time to live of archived auction outcomes and phases (about 90 days).
+pub(crate) const OUTCOME_TTL: u32 = (ledger_times::LEDGERS_PER_DAY * 90) as u32;
+
+// Time to live of the contract instance (about 90 days), extended by auction activity
+// whenever less than 30 days are left.
+const INSTANCE_TTL: u32 = (ledger_times::LEDGERS_PER_DAY * 90) as u32;
+const INSTANCE_TTL_THRESHOLD: u32 = (ledger_times::LEDGERS_PER_DAY * 30) as u32;
+
+// Extend the time to live of the contract instance (admin data, configuration and roles).
+pub(crate) fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL);
+}
+
+// Share of `amount` at `rate` (basis points), rounded up.
+pub fn calculate_share(amount: i128, rate: i128) -> i128 {
+    amount
+        .checked_mul(rate)
+        .and_then(|val| val.checked_add(MAX_BPS - 1))
+        .and_then(|val| val.checked_div(MAX_BPS))
+        .unwrap()
+}
+
+// Transfer `amount` in `market` from the contract to `recipient`.
+fn pay(env: &Env, recipient: &Address, market: &Address, amount: i128) -> Payment {
+    token::Client::new(env, market).transfer(&env.current_contract_address(), recipient, &amount);
+    Payment {
+        recipient: recipient.clone(),
+        market: market.clone(),
+        amount,
+    }
+}
+
+// Event data for a new, raised or repriced bid.
+pub fn bid_placed(auction_id: u64, bid: &BidData) -> BidPlaced {
+    BidPlaced {
+        version: EVENTS_VERSION,
+        auction_id,
+        buyer: bid.buyer.clone(),
+        market: bid.market.clone(),
+        amount: bid.amount,
+        max_amount: bid.max_amount,
+        quantity: bid.quantity,
+        sniper: bid.sniper,
+    }
 }
 
 pub trait BaseAuction {
     fn start(&self, env: &Env, auction_id: u64, auction_data: &AuctionData) {
         assert!(!storage::has::<DataKey, AuctionData>(
-            &env,
+            env,
             &DataKey::AuctionData(auction_id)
         ));
         assert!(auction_data.settings.amount > 0 && auction_data.settings.duration > 0);
 
+        // Sealed bid auctions require both a commit phase and a deposit, other kinds neither.
+        let sealed = self.is_sealed_bid_auction(auction_data);
+        assert_eq!(auction_data.settings.sealed_phase_time > 0, sealed);
+        assert_eq!(auction_data.settings.sealed_bid_deposit > 0, sealed);
+        assert_eq!(auction_data.settings.reveal_phase_time > 0, sealed);
+        assert!(
+            auction_data.reveal_deadline()
+                <= auction_data.start_time + auction_data.settings.duration
+        );
+        assert!(self.validate(&auction_data.settings));
+
+        // Outbid rules only apply to open ascending auctions.
+        let settings = &auction_data.settings;
+        assert!(settings.starting_price >= 0 && settings.min_increment >= 0);
+        assert!(
+            settings.kind == AuctionKind::Ascending
+                || (settings.starting_price == 0
+                    && settings.min_increment == 0
+                    && !settings.relative_increment)
+        );
+
+        // Additional currencies are only accepted by open ascending auctions.
+        assert!(
+            settings.markets.is_empty()
+                || (settings.kind == AuctionKind::Ascending
+                    && !settings.markets.contains(&settings.market))
+        );
+
+        // Soft-close policy is bounded by the admin limits, a zero `window` selects the default.
+        let soft_close = &settings.soft_close;
+        let limits = SoftClosePolicy::load_limits(env);
+        assert!(
+            soft_close.window <= limits.window
+                && soft_close.extension <= limits.extension
+                && soft_close.max_extensions <= limits.max_extensions
+        );
+        assert!(
+            soft_close.window > 0 || (soft_close.extension == 0 && soft_close.max_extensions == 0)
+        );
+
         // Transfer token to contract.
-        let token = token::Client::new(&env, &auction_data.settings.token);
+        let token = token::Client::new(env, &auction_data.settings.token);
         token.transfer(
             &auction_data.settings.seller,
             &env.current_contract_address(),
             &auction_data.settings.amount,
         );
-        storage::set::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id), auction_data);
+        // Stored in its own persistent entry, with TTL (and index entries) extended until the end
+        // of the auction.
+        index::add(env, auction_data);
+        auction_data.store(env);
+        extend_instance_ttl(env);
 
-        fn convert_seconds_to_ledgers(seconds: u64) -> u64 {
-            seconds
-                .checked_add(ledger_times::LEDGERS_PER_MINUTE - 1)
-                .and_then(|sum| sum.checked_div(ledger_times::LEDGERS_PER_MINUTE))
-                .expect("Invalid duration.")
-                .min(ledger_times::LEDGERS_PER_YEAR)
-        }
-
-        // Extend data TTL according to auction duration,
-        // adding a couple hours to avoid expiration with async resolve.
-        let expiration_buffer: u64 = 7200;
-        storage::extend_ttl::<DataKey, AuctionData>(
-            env,
-            &DataKey::AuctionData(auction_id),
-            convert_seconds_to_ledgers(auction_data.settings.duration + expiration_buffer) as u32,
-            convert_seconds_to_ledgers(auction_data.settings.duration + expiration_buffer) as u32,
+        env.events().publish(
+            (AUCTION, symbol_short!("started")),
+            AuctionStarted {
+                version: EVENTS_VERSION,
+                auction_id,
+                kind: settings.kind,
+                seller: settings.seller.clone(),
+                token: settings.token.clone(),
+                amount: settings.amount,
+                market: settings.market.clone(),
+                start_time: auction_data.start_time,
+                end_time: auction_data.start_time + settings.duration,
+            },
         );
 
-        env.events()
-            .publish((AUCTION, symbol_short!("started")), auction_id);
-
         // Set the auction phase.
-        let region = AuctionRegion::Dispatcher(auction_id);
-        let state_machine =
-            StateMachine::<AuctionRegion, AuctionPhase>::new(&region, fsm::StorageType::Instance);
-        match self.is_sealed_bid_auction(&auction_data) {
-            true => {
-                state_machine.set_state(&env, &AuctionPhase::Committing);
-            }
-            false => {
-                state_machine.set_state(&env, &AuctionPhase::Running);
-            }
-        }
+        self.advance_phase(env, auction_id);
     }
 
     fn place_sealed_bid(
@@ -88,10 +183,9 @@ pub trait BaseAuction {
         buyer: &Address,
         sealed_amount: &BytesN<32>,
     ) {
-        self.commit_bid(env, sealed_amount);
+        self.commit_bid(env, auction_id, buyer, sealed_amount);
 
-        let mut auction_data =
-            storage::get::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id)).unwrap();
+        let mut auction_data = AuctionData::load(env, auction_id).unwrap();
 
         if auction_data
             .deposits
@@ -101,9 +195,9 @@ pub trait BaseAuction {
             panic!("Not allowed");
         } else {
             // Deposit the requested amount.
-            let market = token::Client::new(&env, &auction_data.settings.market);
+            let market = token::Client::new(env, &auction_data.settings.market);
             market.transfer(
-                &buyer,
+                buyer,
                 &env.current_contract_address(),
                 &auction_data.settings.sealed_bid_deposit,
             );
@@ -111,50 +205,69 @@ pub trait BaseAuction {
                 buyer: buyer.clone(),
                 amount: auction_data.settings.sealed_bid_deposit,
                 sniper: false,
+                quantity: 0,
+                max_amount: 0,
+                market: auction_data.settings.market.clone(),
             });
-            env.events()
-                .publish((BID, symbol_short!("sealed")), auction_id);
+            env.events().publish(
+                (BID, symbol_short!("sealed")),
+                BidSealed {
+                    version: EVENTS_VERSION,
+                    auction_id,
+                    buyer: buyer.clone(),
+                    market: auction_data.settings.market.clone(),
+                    deposit: auction_data.settings.sealed_bid_deposit,
+                },
+            );
         }
 
-        storage::set::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id), &auction_data);
+        auction_data.store(env);
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn place_bid(
         &self,
         env: &Env,
         auction_id: u64,
         buyer: &Address,
+        market: &Address,
         amount: i128,
+        quantity: i128,
         salt: &Option<BytesN<32>>,
     ) {
+        extend_instance_ttl(env);
+
         // First check that the auction is resolved.
         let resolved = self.resolve(env, auction_id);
         if resolved {
             return;
         }
 
-        let mut auction_data =
-            storage::get::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id)).unwrap();
-        let market = token::Client::new(&env, &auction_data.settings.market);
+        let mut auction_data = AuctionData::load(env, auction_id).unwrap();
+        self.assert_bidding_open(env, &auction_data);
+        assert!(auction_data.settings.accepts(market));
 
         // Reveal the sealed bid.
         match self.is_sealed_bid_auction(&auction_data) {
             true => {
                 // Reveal the sealed bid.
-                let mut data = Bytes::from_array(&env, &amount.to_be_bytes());
-                data.append(&Bytes::from_slice(&env, &salt.as_ref().unwrap().to_array()));
-                data.append(&Bytes::from_slice(&env, &auction_data.id.to_be_bytes()));
-                self.reveal_bid(env, &data);
+                self.reveal_bid(env, auction_id, buyer, amount, salt.as_ref().unwrap());
 
                 // Refund the deposit.
                 if let Some(index) = auction_data.deposits.iter().position(|b| {
                     b.amount == auction_data.settings.sealed_bid_deposit && b.buyer == *buyer
                 }) {
                     let bid = &auction_data.deposits.get_unchecked(index as u32);
-                    market.transfer(&env.current_contract_address(), &buyer, &bid.amount);
+                    let refund = pay(env, buyer, &bid.market, bid.amount);
                     auction_data.deposits.remove(index as u32);
-                    env.events()
-                        .publish((BID, symbol_short!("refunded")), auction_id);
+                    env.events().publish(
+                        (BID, symbol_short!("refunded")),
+                        BidRefunded {
+                            version: EVENTS_VERSION,
+                            auction_id,
+                            refund,
+                        },
+                    );
                 } else {
                     panic!("Invalid bid");
                 }
@@ -170,37 +283,46 @@ pub trait BaseAuction {
                 .position(|b| b.amount > 0 && b.buyer == *buyer && !b.sniper)
             {
                 let bid = &auction_data.bids.get_unchecked(index as u32);
-                market.transfer(&env.current_contract_address(), &buyer, &bid.amount);
+                let refund = pay(env, buyer, &bid.market, bid.escrow());
                 auction_data.bids.remove(index as u32);
-                env.events()
-                    .publish((BID, symbol_short!("deleted")), auction_id);
+                env.events().publish(
+                    (BID, symbol_short!("deleted")),
+                    BidRefunded {
+                        version: EVENTS_VERSION,
+                        auction_id,
+                        refund,
+                    },
+                );
             } else {
                 panic!("No bid to cancel");
             }
-        } else if amount > 0 && amount >= auction_data.settings.reserve_price {
+        } else if amount > 0
+            && auction_data.settings.normalize(env, market, amount)
+                >= self.calculate_min_bid(env, &auction_data, quantity)
+        {
             if !auction_data
                 .bids
                 .iter()
-                .any(|b| (b.buyer == *buyer && b.amount > 0) || (b.buyer == *buyer && b.sniper))
+                .any(|b| b.buyer == *buyer && (b.amount > 0 || b.sniper))
             {
-                market.transfer(&buyer, &env.current_contract_address(), &amount);
-
-                let anti_snipe_time = storage::get::<DataKey, AdminData>(&env, &DataKey::AdminData)
-                    .unwrap()
-                    .anti_snipe_time;
-                let sniper = env.ledger().timestamp()
-                    >= auction_data.start_time + auction_data.settings.duration - anti_snipe_time;
-                if sniper {
-                    auction_data.settings.duration += anti_snipe_time;
-                }
+                token::Client::new(env, market).transfer(
+                    buyer,
+                    &env.current_contract_address(),
+                    &amount,
+                );
 
-                auction_data.bids.push_back(BidData {
+                let sniper = self.apply_anti_snipe(env, &mut auction_data);
+                let bid = BidData {
                     buyer: buyer.clone(),
                     amount,
                     sniper,
-                });
+                    quantity,
+                    max_amount: 0,
+                    market: market.clone(),
+                };
                 env.events()
-                    .publish((BID, symbol_short!("added")), auction_id);
+                    .publish((BID, symbol_short!("added")), bid_placed(auction_id, &bid));
+                auction_data.bids.push_back(bid);
             } else {
                 panic!("Not allowed to place new bid");
             }
@@ -208,123 +330,621 @@ pub trait BaseAuction {
             panic!("Invalid bid");
         }
 
-        storage::set::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id), &auction_data);
+        self.reprice(env, &mut auction_data);
+        auction_data.store(env);
         self.resolve(env, auction_id);
     }
 
-    fn finalize(&self, env: &Env, auction_id: u64, winner: Option<&BidData>) -> bool {
-        let auction_data =
-            storage::get::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id)).unwrap();
-        match winner {
-            Some(bid) => {
-                // We have a winner, transfer token to parties.
-                let admin_data =
-                    storage::get::<DataKey, AdminData>(&env, &DataKey::AdminData).unwrap();
-                let token = token::Client::new(&env, &auction_data.settings.token);
-                let market = token::Client::new(&env, &auction_data.settings.market);
-                let admin: Address = admin_data.admin;
-                let commission_rate: i128 = admin_data.commission_rate as i128;
-                let admin_share = bid
-                    .amount
-                    .checked_mul(commission_rate)
-                    .and_then(|val| val.checked_add(99))
-                    .and_then(|val| val.checked_div(100))
-                    .unwrap()
-                    .max(1);
-                let seller_share = bid.amount.checked_sub(admin_share).unwrap().max(1);
+    fn place_proxy_bid(&self, env: &Env, auction_id: u64, buyer: &Address, max_amount: i128) {
+        // First check that the auction is resolved.
+        let resolved = self.resolve(env, auction_id);
+        if resolved {
+            return;
+        }
 
-                token.transfer(
-                    &env.current_contract_address(),
-                    &bid.buyer,
-                    &auction_data.settings.amount,
-                );
-                market.transfer(&env.current_contract_address(), &admin, &admin_share);
-                market.transfer(
-                    &env.current_contract_address(),
-                    &auction_data.settings.seller,
-                    &seller_share,
-                );
+        let mut auction_data = AuctionData::load(env, auction_id).unwrap();
+        self.assert_bidding_open(env, &auction_data);
+        assert!(!self.is_sealed_bid_auction(&auction_data));
 
-                // Cancel all other bids.
-                let market = token::Client::new(&env, &auction_data.settings.market);
-                for b in auction_data.bids.iter() {
-                    if b.amount > 0 && b.buyer != bid.buyer {
-                        market.transfer(&env.current_contract_address(), &b.buyer, &b.amount);
-                    }
-                }
+        let quantity = auction_data.settings.amount;
+        if max_amount <= 0 || max_amount < self.calculate_min_bid(env, &auction_data, quantity) {
+            panic!("Invalid bid");
+        }
+        if auction_data
+            .bids
+            .iter()
+            .any(|b| b.buyer == *buyer && (b.amount > 0 || b.sniper))
+        {
+            panic!("Not allowed to place new bid");
+        }
 
-                let region = &AuctionRegion::Dispatcher(auction_id);
-                let state_machine = StateMachine::<AuctionRegion, AuctionPhase>::new(
-                    region,
-                    fsm::StorageType::Instance,
-                );
-                state_machine.remove_state(&env);
+        // Escrow the maximum, the visible price is set by `reprice`.
+        let market = token::Client::new(env, &auction_data.settings.market);
+        market.transfer(buyer, &env.current_contract_address(), &max_amount);
 
-                // Delete the auction.
-                storage::remove::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id));
-                env.events()
-                    .publish((AUCTION, symbol_short!("won")), auction_id);
-                true
+        let sniper = self.apply_anti_snipe(env, &mut auction_data);
+        auction_data.bids.push_back(BidData {
+            buyer: buyer.clone(),
+            amount: 0,
+            sniper,
+            quantity,
+            max_amount,
+            market: auction_data.settings.market.clone(),
+        });
+
+        // Report the visible price.
+        self.reprice(env, &mut auction_data);
+        env.events().publish(
+            (BID, symbol_short!("added")),
+            bid_placed(auction_id, &auction_data.bids.last_unchecked()),
+        );
+        auction_data.store(env);
+        self.resolve(env, auction_id);
+    }
+
+    fn raise_bid(&self, env: &Env, auction_id: u64, buyer: &Address, amount: i128) {
+        // First check that the auction is resolved.
+        let resolved = self.resolve(env, auction_id);
+        if resolved {
+            return;
+        }
+
+        let mut auction_data = AuctionData::load(env, auction_id).unwrap();
+        self.assert_bidding_open(env, &auction_data);
+        assert!(!self.is_sealed_bid_auction(&auction_data));
+
+        if let Some(index) = auction_data
+            .bids
+            .iter()
+            .position(|b| b.amount > 0 && b.buyer == *buyer)
+        {
+            let mut bid = auction_data.bids.get_unchecked(index as u32);
+            if amount <= bid.escrow()
+                || auction_data.settings.normalize(env, &bid.market, amount)
+                    < self.calculate_min_bid(env, &auction_data, bid.quantity)
+            {
+                panic!("Invalid bid");
             }
+
+            // Escrow the difference only, in the currency of the bid.
+            token::Client::new(env, &bid.market).transfer(
+                buyer,
+                &env.current_contract_address(),
+                &(amount - bid.escrow()),
+            );
+
+            // Raising within the anti-snipe window extends the auction again.
+            // Proxy bids raise their maximum.
+            bid.sniper |= self.apply_anti_snipe(env, &mut auction_data);
+            match bid.max_amount > 0 {
+                true => bid.max_amount = amount,
+                false => bid.amount = amount,
+            }
+            auction_data.bids.set(index as u32, bid);
+            self.reprice(env, &mut auction_data);
+            env.events().publish(
+                (BID, symbol_short!("raised")),
+                bid_placed(auction_id, &auction_data.bids.get_unchecked(index as u32)),
+            );
+        } else {
+            panic!("No bid to raise");
+        }
+
+        auction_data.store(env);
+        self.resolve(env, auction_id);
+    }
+
+    // Bids are accepted until the end of the auction, even while its settlement is
+    // pending (e.g., hidden reserve not yet revealed).
+    fn assert_bidding_open(&self, env: &Env, auction_data: &AuctionData) {
+        assert!(
+            env.ledger().timestamp() <= auction_data.start_time + auction_data.settings.duration,
+            "Auction has ended"
+        );
+    }
+
+    // Flag bids within the soft-close window as snipers, return true if so.
+    // The auction is extended unless the policy's maximum extensions is reached.
+    fn apply_anti_snipe(&self, env: &Env, auction_data: &mut AuctionData) -> bool {
+        let policy = auction_data.settings.soft_close.resolve(env);
+        let end_time = auction_data.start_time + auction_data.settings.duration;
+        let sniper = env.ledger().timestamp() >= end_time.saturating_sub(policy.window);
+        if sniper && policy.extension > 0 && auction_data.extensions < policy.max_extensions {
+            auction_data.settings.duration += policy.extension;
+            auction_data.extensions += 1;
+            env.events().publish(
+                (BID, symbol_short!("extended")),
+                AuctionExtended {
+                    version: EVENTS_VERSION,
+                    auction_id: auction_data.id,
+                    end_time: end_time + policy.extension,
+                    extensions: auction_data.extensions,
+                },
+            );
+        }
+        sniper
+    }
+
+    // Update the visible price of proxy bids, reporting visible bids whose price changed.
+    fn reprice(&self, _env: &Env, _auction_data: &mut AuctionData) {}
+
+    fn finalize(&self, env: &Env, auction_id: u64, winner: Option<&BidData>) -> bool {
+        match winner {
+            Some(bid) => self.finalize_winners(env, auction_id, &vec![env, bid.clone()]),
             None => {
+                let auction_data = AuctionData::load(env, auction_id).unwrap();
+
                 // No winner.
                 // Transfer token back to seller.
-                let token = token::Client::new(&env, &auction_data.settings.token);
+                let token = token::Client::new(env, &auction_data.settings.token);
                 token.transfer(
                     &env.current_contract_address(),
                     &auction_data.settings.seller,
                     &auction_data.settings.amount,
                 );
 
-                // Cancel all bids.
-                let market = token::Client::new(&env, &auction_data.settings.market);
+                // Cancel all bids, unrevealed deposits are forfeited.
+                self.forfeit_deposits(env, &auction_data);
+                let mut refunds = Vec::new(env);
                 for bid in auction_data.bids.iter() {
-                    if bid.amount > 0 {
-                        market.transfer(&env.current_contract_address(), &bid.buyer, &bid.amount);
+                    if bid.escrow() > 0 {
+                        refunds.push_back(pay(env, &bid.buyer, &bid.market, bid.escrow()));
                     }
                 }
 
-                let region = &AuctionRegion::Dispatcher(auction_id);
-                let state_machine = StateMachine::<AuctionRegion, AuctionPhase>::new(
-                    region,
-                    fsm::StorageType::Instance,
+                self.close(env, &auction_data, OutcomeKind::Ended, &Vec::new(env), 0);
+                env.events().publish(
+                    (AUCTION, symbol_short!("ended")),
+                    AuctionSettled {
+                        version: EVENTS_VERSION,
+                        auction_id,
+                        winners: Vec::new(env),
+                        clearing_price: 0,
+                        refunds,
+                    },
                 );
-                state_machine.remove_state(&env);
-
-                // Delete the auction.
-                storage::remove::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id));
-                env.events()
-                    .publish((AUCTION, symbol_short!("ended")), auction_id);
                 true
             }
         }
     }
 
+    fn cancel(&self, env: &Env, auction_id: u64) {
+        let auction_data = AuctionData::load(env, auction_id).unwrap();
+
+        // Ended auctions are settled by `resolve`, winning bids can no longer be withdrawn.
+        assert!(
+            env.ledger().timestamp() <= auction_data.start_time + auction_data.settings.duration,
+            "Auction has ended"
+        );
+        let policy = env
+            .storage()
+            .instance()
+            .get::<DataKey, CancellationPolicy>(&DataKey::CancellationPolicy)
+            .unwrap_or(CancellationPolicy {
+                allow_with_bids: false,
+                penalty_rate: 0,
+            });
+
+        // Bids meeting the reserve either prevent cancellation or incur a penalty
+        // (basis points of the highest bid, in the auction `market`) paid by the seller to the treasury.
+        let settings = &auction_data.settings;
+        let mut penalty = 0;
+        if let Some(value) = auction_data
+            .bids
+            .iter()
+            .map(|b| (b.value(env, settings), b.quantity))
+            .filter(|(value, quantity)| *value >= self.calculate_reserve(&auction_data, *quantity))
+            .map(|(value, _)| value)
+            .max()
+        {
+            assert!(policy.allow_with_bids, "Auction has bids");
+            penalty = calculate_share(value, policy.penalty_rate);
+            if penalty > 0 {
+                token::Client::new(env, &settings.market).transfer(
+                    &auction_data.settings.seller,
+                    &Role::Treasury.holder(env),
+                    &penalty,
+                );
+            }
+        }
+
+        // Transfer token back to seller.
+        let token = token::Client::new(env, &auction_data.settings.token);
+        token.transfer(
+            &env.current_contract_address(),
+            &auction_data.settings.seller,
+            &auction_data.settings.amount,
+        );
+
+        // Refund all bids and sealed bid deposits.
+        for bid in auction_data.deposits.iter() {
+            env.storage()
+                .persistent()
+                .remove::<DataKey>(&DataKey::Commitment(auction_id, bid.buyer));
+        }
+        let mut refunds = Vec::new(env);
+        for bid in auction_data.bids.iter().chain(auction_data.deposits.iter()) {
+            if bid.escrow() > 0 {
+                refunds.push_back(pay(env, &bid.buyer, &bid.market, bid.escrow()));
+            }
+        }
+
+        self.close(
+            env,
+            &auction_data,
+            OutcomeKind::Cancelled,
+            &Vec::new(env),
+            0,
+        );
+        env.events().publish(
+            (AUCTION, symbol_short!("cancelled")),
+            AuctionCancelled {
+                version: EVENTS_VERSION,
+                auction_id,
+                penalty,
+                refunds,
+            },
+        );
+    }
+
+    // Each winning bid receives `quantity` units and pays its `amount`, in the currency of the bid.
+    fn finalize_winners(&self, env: &Env, auction_id: u64, winners: &Vec<BidData>) -> bool {
+        let auction_data = AuctionData::load(env, auction_id).unwrap();
+
+        // We have winners, transfer token to parties.
+        let admin_data = AdminData::load(env);
+        let token = token::Client::new(env, &auction_data.settings.token);
+        let treasury = Role::Treasury.holder(env);
+        let royalty_contract = env
+            .storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::RoyaltyContract);
+        let mut sold = 0;
+        let mut settlements = Vec::new(env);
+        let mut clearing_price = 0;
+        for bid in winners.iter() {
+            // Commission is rounded up, the seller share is the exact remainder.
+            let admin_share = calculate_share(bid.amount, admin_data.commission_rate);
+            let seller_share = bid.amount - admin_share;
+
+            token.transfer(&env.current_contract_address(), &bid.buyer, &bid.quantity);
+            let market = token::Client::new(env, &bid.market);
+            if admin_share > 0 {
+                market.transfer(&env.current_contract_address(), &treasury, &admin_share);
+            }
+
+            // Route the royalty out of the seller share.
+            let royalty = royalty_contract.as_ref().map_or(0, |royalty_contract| {
+                pay_royalty(
+                    env,
+                    auction_id,
+                    royalty_contract,
+                    &auction_data.settings.token,
+                    &bid.market,
+                    &bid.buyer,
+                    seller_share,
+                )
+            });
+            if seller_share > royalty {
+                market.transfer(
+                    &env.current_contract_address(),
+                    &auction_data.settings.seller,
+                    &(seller_share - royalty),
+                );
+            }
+            sold += bid.quantity;
+
+            // Multi-unit winners pay the same (exact) unit price, single winners their amount.
+            let price = match auction_data.settings.kind {
+                AuctionKind::MultiUnit => bid.amount / bid.quantity,
+                _ => bid.amount,
+            };
+            if settlements.is_empty() || price < clearing_price {
+                clearing_price = price;
+            }
+            settlements.push_back(Settlement {
+                buyer: bid.buyer.clone(),
+                market: bid.market.clone(),
+                quantity: bid.quantity,
+                amount: bid.amount,
+                admin_share,
+                royalty,
+                seller_share: (seller_share - royalty).max(0),
+            });
+        }
+
+        // Transfer unsold units back to seller.
+        if auction_data.settings.amount > sold {
+            token.transfer(
+                &env.current_contract_address(),
+                &auction_data.settings.seller,
+                &(auction_data.settings.amount - sold),
+            );
+        }
+
+        // Unrevealed deposits are forfeited.
+        self.forfeit_deposits(env, &auction_data);
+
+        // Cancel all other bids and refund the winners' escrow above the price.
+        let mut refunds = Vec::new(env);
+        for b in auction_data.bids.iter() {
+            let paid = winners
+                .iter()
+                .find(|w| w.buyer == b.buyer)
+                .map_or(0, |w| w.amount);
+            if b.escrow() > paid {
+                refunds.push_back(pay(env, &b.buyer, &b.market, b.escrow() - paid));
+            }
+        }
+
+        self.close(
+            env,
+            &auction_data,
+            OutcomeKind::Won,
+            &settlements,
+            clearing_price,
+        );
+        env.events().publish(
+            (AUCTION, symbol_short!("won")),
+            AuctionSettled {
+                version: EVENTS_VERSION,
+                auction_id,
+                winners: settlements,
+                clearing_price,
+                refunds,
+            },
+        );
+        true
+    }
+
+    // Set the final phase, archive the outcome and delete the auction.
+    fn close(
+        &self,
+        env: &Env,
+        auction_data: &AuctionData,
+        outcome: OutcomeKind,
+        winners: &Vec<Settlement>,
+        clearing_price: i128,
+    ) {
+        let region = &AuctionRegion::Dispatcher(auction_data.id);
+        let state_machine =
+            StateMachine::<AuctionRegion, AuctionPhase>::new(region, fsm::StorageType::Persistent);
+        state_machine.set_state(
+            env,
+            &match outcome {
+                OutcomeKind::Cancelled => AuctionPhase::Cancelled,
+                _ => AuctionPhase::Settled,
+            },
+        );
+        env.storage()
+            .persistent()
+            .extend_ttl(region, OUTCOME_TTL, OUTCOME_TTL);
+
+        // Archive the outcome in persistent storage (see `OUTCOME_TTL`).
+        let settings = &auction_data.settings;
+        let key = DataKey::AuctionOutcome(auction_data.id);
+        storage::set::<DataKey, AuctionOutcome>(
+            env,
+            &key,
+            &AuctionOutcome {
+                id: auction_data.id,
+                outcome,
+                kind: settings.kind,
+                seller: settings.seller.clone(),
+                token: settings.token.clone(),
+                amount: settings.amount,
+                market: settings.market.clone(),
+                reserve_price: settings.reserve_price,
+                winners: winners.clone(),
+                clearing_price,
+                end_time: auction_data.start_time + settings.duration,
+                settled_time: env.ledger().timestamp(),
+            },
+        );
+        storage::extend_ttl::<DataKey, AuctionOutcome>(env, &key, OUTCOME_TTL, OUTCOME_TTL);
+
+        // Delete the auction.
+        storage::remove::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_data.id));
+        env.storage()
+            .persistent()
+            .remove::<DataKey>(&DataKey::HiddenReserve(auction_data.id));
+        index::remove(env, auction_data);
+    }
+
+    // Reveal the hidden reserve price, at or after the end of the auction and before
+    // the reveal deadline (see `RESERVE_REVEAL_TIME`), then resolve the auction.
+    fn reveal_reserve(&self, env: &Env, auction_id: u64, reserve: i128, salt: &BytesN<32>) {
+        let mut auction_data = AuctionData::load(env, auction_id).unwrap();
+        let key = DataKey::HiddenReserve(auction_id);
+        let reserve_hash = env
+            .storage()
+            .persistent()
+            .get::<DataKey, BytesN<32>>(&key)
+            .expect("No hidden reserve");
+
+        let end_time = auction_data.start_time + auction_data.settings.duration;
+        let now = env.ledger().timestamp();
+        assert!(now >= end_time && now <= end_time + RESERVE_REVEAL_TIME);
+
+        let mut data = Bytes::from_array(env, &reserve.to_be_bytes());
+        data.append(&Bytes::from_slice(env, &salt.to_array()));
+        assert_eq!(env.crypto().sha256(&data), reserve_hash);
+        assert!(reserve >= 0);
+
+        env.storage().persistent().remove::<DataKey>(&key);
+        auction_data.settings.reserve_price = reserve;
+        auction_data.store(env);
+        env.events().publish(
+            (AUCTION, symbol_short!("reserve")),
+            ReserveRevealed {
+                version: EVENTS_VERSION,
+                auction_id,
+                reserve,
+            },
+        );
+        self.resolve(env, auction_id);
+    }
+
+    // Phase of an active auction according to the ledger time.
+    // Sealed bid auctions are committing until `sealed_phase_time`, then revealing until the reveal deadline.
+    fn calculate_phase(&self, env: &Env, auction_data: &AuctionData) -> AuctionPhase {
+        let now = env.ledger().timestamp();
+        if now < auction_data.start_time {
+            AuctionPhase::Scheduled
+        } else if !self.is_sealed_bid_auction(auction_data) {
+            AuctionPhase::Running
+        } else if now < auction_data.start_time + auction_data.settings.sealed_phase_time {
+            AuctionPhase::Committing
+        } else if now <= auction_data.reveal_deadline() {
+            AuctionPhase::Revealing
+        } else {
+            AuctionPhase::Running
+        }
+    }
+
+    // Apply the time-based transitions of an active auction, return the current phase.
+    fn advance_phase(&self, env: &Env, auction_id: u64) -> AuctionPhase {
+        let auction_data = AuctionData::load(env, auction_id).unwrap();
+        let phase = self.calculate_phase(env, &auction_data);
+        let region = AuctionRegion::Dispatcher(auction_id);
+        let state_machine =
+            StateMachine::<AuctionRegion, AuctionPhase>::new(&region, fsm::StorageType::Persistent);
+        if state_machine.get_state(env).as_ref() != Some(&phase) {
+            state_machine.set_state(env, &phase);
+            auction_data.extend_ttl(env);
+        }
+        phase
+    }
+
+    // Forfeit the remaining (unrevealed) sealed bid deposits.
+    // The treasury receives the forfeiture rate (basis points), the seller the remainder.
+    fn forfeit_deposits(&self, env: &Env, auction_data: &AuctionData) {
+        for bid in auction_data.deposits.iter() {
+            env.storage()
+                .persistent()
+                .remove::<DataKey>(&DataKey::Commitment(auction_data.id, bid.buyer));
+        }
+        let total: i128 = auction_data.deposits.iter().map(|b| b.amount).sum();
+        if total == 0 {
+            return;
+        }
+
+        let rate = env
+            .storage()
+            .instance()
+            .get::<DataKey, i128>(&DataKey::ForfeitureRate)
+            .unwrap_or(0);
+        let admin_share = calculate_share(total, rate);
+        let market = token::Client::new(env, &auction_data.settings.market);
+        if admin_share > 0 {
+            market.transfer(
+                &env.current_contract_address(),
+                &Role::Treasury.holder(env),
+                &admin_share,
+            );
+        }
+        if total > admin_share {
+            market.transfer(
+                &env.current_contract_address(),
+                &auction_data.settings.seller,
+                &(total - admin_share),
+            );
+        }
+        env.events().publish(
+            (BID, symbol_short!("forfeited")),
+            DepositsForfeited {
+                version: EVENTS_VERSION,
+                auction_id: auction_data.id,
+                market: auction_data.settings.market.clone(),
+                total,
+                admin_share,
+                seller_share: total - admin_share,
+            },
+        );
+    }
+
+    // Minimum bid amount for the requested quantity.
+    // Single lot auctions only accept bids for the whole lot.
+    fn calculate_reserve(&self, auction_data: &AuctionData, quantity: i128) -> i128 {
+        assert_eq!(quantity, auction_data.settings.amount);
+        auction_data.settings.reserve_price
+    }
+
+    // Minimum acceptable bid amount for the requested quantity, in the auction `market`.
+    fn calculate_min_bid(&self, _env: &Env, auction_data: &AuctionData, quantity: i128) -> i128 {
+        self.calculate_reserve(auction_data, quantity)
+    }
+
     fn is_sealed_bid_auction(&self, auction_data: &AuctionData) -> bool {
-        auction_data.settings.sealed_bid_deposit > 0
-            && auction_data.settings.sealed_phase_time > 0
-            && auction_data.settings.discount_percent == 0
-            && auction_data.settings.discount_frequency == 0
+        matches!(
+            auction_data.settings.kind,
+            AuctionKind::SealedBid | AuctionKind::SecondPrice
+        )
     }
 
-    // Commit.
-    #[commit(hash = "sealed")]
-    fn commit_bid(&self, env: &Env, sealed: &BytesN<32>) {}
+    // Commit, one sealed bid per buyer and auction.
+    fn commit_bid(&self, env: &Env, auction_id: u64, buyer: &Address, sealed: &BytesN<32>) {
+        let key = DataKey::Commitment(auction_id, buyer.clone());
+        assert!(!env.storage().persistent().has::<DataKey>(&key));
+        env.storage()
+            .persistent()
+            .set::<DataKey, BytesN<32>>(&key, sealed);
+    }
+
+    // Reveal, the preimage must match the buyer's own commitment.
+    // Sealed bids of legacy auctions are committed under their hash in instance storage,
+    // with a preimage not bound to the buyer (who must still hold a deposit).
+    fn reveal_bid(
+        &self,
+        env: &Env,
+        auction_id: u64,
+        buyer: &Address,
+        amount: i128,
+        salt: &BytesN<32>,
+    ) {
+        let mut data = Bytes::from_array(env, &amount.to_be_bytes());
+        data.append(&Bytes::from_slice(env, &salt.to_array()));
+        data.append(&Bytes::from_slice(env, &auction_id.to_be_bytes()));
+        let legacy_sealed = env.crypto().sha256(&data);
+        data.append(&buyer.clone().to_xdr(env));
 
-    // Reveal.
-    #[reveal(data = "amount")]
-    fn reveal_bid(&self, env: &Env, amount: &Bytes) {}
+        let key = DataKey::Commitment(auction_id, buyer.clone());
+        match env.storage().persistent().get::<DataKey, BytesN<32>>(&key) {
+            Some(sealed) if sealed == env.crypto().sha256(&data) => {
+                env.storage().persistent().remove::<DataKey>(&key);
+            }
+            None if env.storage().instance().has(&legacy_sealed) => {
+                env.storage().instance().remove(&legacy_sealed);
+            }
+            _ => panic!("Invalid bid"),
+        }
+    }
 
     fn resolve(&self, env: &Env, auction_id: u64) -> bool;
 
+    fn validate(&self, settings: &AuctionSettings) -> bool;
+
     fn calculate_price(&self, env: &Env, auction_id: u64) -> i128;
 }
 
 // The Dispatcher uses the `state-machine` to control auction phases
-#[derive(TransitionHandler)]
+#[allow(clippy::enum_variant_names)]
 pub enum Dispatcher {
     AscendingPriceAuction,
     DescendingPriceAuction,
+    SecondPriceAuction,
+    UniformPriceAuction,
+}
+
+// Time-based transitions are applied before guarding the phase, so any caller can trigger them.
+impl TransitionHandler<AuctionRegion, AuctionPhase> for Dispatcher {
+    fn on_guard(&self, env: &Env, state_machine: &StateMachine<AuctionRegion, AuctionPhase>) {
+        if let AuctionRegion::Dispatcher(auction_id) = state_machine.get_region() {
+            self.advance_phase(env, *auction_id);
+        }
+    }
+
+    fn on_effect(&self, _env: &Env, _state_machine: &StateMachine<AuctionRegion, AuctionPhase>) {}
 }
 
 impl BaseAuction for Dispatcher {
@@ -336,12 +956,19 @@ impl BaseAuction for Dispatcher {
             Dispatcher::DescendingPriceAuction => {
                 DescendingPriceAuction.start(env, auction_id, auction_data)
             }
+            Dispatcher::SecondPriceAuction => {
+                SecondPriceAuction.start(env, auction_id, auction_data)
+            }
+            Dispatcher::UniformPriceAuction => {
+                UniformPriceAuction.start(env, auction_id, auction_data)
+            }
         }
     }
 
     #[state_machine(
         state = "AuctionPhase:Committing",
-        region = "AuctionRegion:Dispatcher:auction_id"
+        region = "AuctionRegion:Dispatcher:auction_id",
+        storage = "persistent"
     )]
     fn place_sealed_bid(
         &self,
@@ -357,27 +984,104 @@ impl BaseAuction for Dispatcher {
             Dispatcher::DescendingPriceAuction => {
                 DescendingPriceAuction.place_sealed_bid(env, auction_id, buyer, sealed_amount)
             }
+            Dispatcher::SecondPriceAuction => {
+                SecondPriceAuction.place_sealed_bid(env, auction_id, buyer, sealed_amount)
+            }
+            Dispatcher::UniformPriceAuction => {
+                UniformPriceAuction.place_sealed_bid(env, auction_id, buyer, sealed_amount)
+            }
         }
     }
 
-    #[state_machine(
-        state = "AuctionPhase:Running",
-        region = "AuctionRegion:Dispatcher:auction_id"
-    )]
+    #[allow(clippy::too_many_arguments)]
     fn place_bid(
         &self,
         env: &Env,
         auction_id: u64,
         buyer: &Address,
+        market: &Address,
         amount: i128,
+        quantity: i128,
         salt: &Option<BytesN<32>>,
     ) {
+        // Sealed bids are revealed in `revealing` phase, open bids placed in `running` phase.
+        let auction_data = AuctionData::load(env, auction_id).unwrap();
+        let phase = match self.is_sealed_bid_auction(&auction_data) {
+            true => AuctionPhase::Revealing,
+            false => AuctionPhase::Running,
+        };
+        assert_eq!(self.advance_phase(env, auction_id), phase);
+
+        match self {
+            Dispatcher::AscendingPriceAuction => AscendingPriceAuction
+                .place_bid(env, auction_id, buyer, market, amount, quantity, salt),
+            Dispatcher::DescendingPriceAuction => DescendingPriceAuction
+                .place_bid(env, auction_id, buyer, market, amount, quantity, salt),
+            Dispatcher::SecondPriceAuction => {
+                SecondPriceAuction.place_bid(env, auction_id, buyer, market, amount, quantity, salt)
+            }
+            Dispatcher::UniformPriceAuction => UniformPriceAuction
+                .place_bid(env, auction_id, buyer, market, amount, quantity, salt),
+        }
+    }
+
+    #[state_machine(
+        state = "AuctionPhase:Running",
+        region = "AuctionRegion:Dispatcher:auction_id",
+        storage = "persistent"
+    )]
+    fn place_proxy_bid(&self, env: &Env, auction_id: u64, buyer: &Address, max_amount: i128) {
+        match self {
+            Dispatcher::AscendingPriceAuction => {
+                AscendingPriceAuction.place_proxy_bid(env, auction_id, buyer, max_amount)
+            }
+            Dispatcher::DescendingPriceAuction => {
+                DescendingPriceAuction.place_proxy_bid(env, auction_id, buyer, max_amount)
+            }
+            Dispatcher::SecondPriceAuction => {
+                SecondPriceAuction.place_proxy_bid(env, auction_id, buyer, max_amount)
+            }
+            Dispatcher::UniformPriceAuction => {
+                UniformPriceAuction.place_proxy_bid(env, auction_id, buyer, max_amount)
+            }
+        }
+    }
+
+    #[state_machine(
+        state = "AuctionPhase:Running",
+        region = "AuctionRegion:Dispatcher:auction_id",
+        storage = "persistent"
+    )]
+    fn raise_bid(&self, env: &Env, auction_id: u64, buyer: &Address, amount: i128) {
         match self {
             Dispatcher::AscendingPriceAuction => {
-                AscendingPriceAuction.place_bid(env, auction_id, buyer, amount, salt)
+                AscendingPriceAuction.raise_bid(env, auction_id, buyer, amount)
             }
             Dispatcher::DescendingPriceAuction => {
-                DescendingPriceAuction.place_bid(env, auction_id, buyer, amount, salt)
+                DescendingPriceAuction.raise_bid(env, auction_id, buyer, amount)
+            }
+            Dispatcher::SecondPriceAuction => {
+                SecondPriceAuction.raise_bid(env, auction_id, buyer, amount)
+            }
+            Dispatcher::UniformPriceAuction => {
+                UniformPriceAuction.raise_bid(env, auction_id, buyer, amount)
+            }
+        }
+    }
+
+    fn reveal_reserve(&self, env: &Env, auction_id: u64, reserve: i128, salt: &BytesN<32>) {
+        match self {
+            Dispatcher::AscendingPriceAuction => {
+                AscendingPriceAuction.reveal_reserve(env, auction_id, reserve, salt)
+            }
+            Dispatcher::DescendingPriceAuction => {
+                DescendingPriceAuction.reveal_reserve(env, auction_id, reserve, salt)
+            }
+            Dispatcher::SecondPriceAuction => {
+                SecondPriceAuction.reveal_reserve(env, auction_id, reserve, salt)
+            }
+            Dispatcher::UniformPriceAuction => {
+                UniformPriceAuction.reveal_reserve(env, auction_id, reserve, salt)
             }
         }
     }
@@ -386,6 +1090,43 @@ impl BaseAuction for Dispatcher {
         match self {
             Dispatcher::AscendingPriceAuction => AscendingPriceAuction.resolve(env, auction_id),
             Dispatcher::DescendingPriceAuction => DescendingPriceAuction.resolve(env, auction_id),
+            Dispatcher::SecondPriceAuction => SecondPriceAuction.resolve(env, auction_id),
+            Dispatcher::UniformPriceAuction => UniformPriceAuction.resolve(env, auction_id),
+        }
+    }
+
+    fn cancel(&self, env: &Env, auction_id: u64) {
+        match self {
+            Dispatcher::AscendingPriceAuction => AscendingPriceAuction.cancel(env, auction_id),
+            Dispatcher::DescendingPriceAuction => DescendingPriceAuction.cancel(env, auction_id),
+            Dispatcher::SecondPriceAuction => SecondPriceAuction.cancel(env, auction_id),
+            Dispatcher::UniformPriceAuction => UniformPriceAuction.cancel(env, auction_id),
+        }
+    }
+
+    fn calculate_min_bid(&self, env: &Env, auction_data: &AuctionData, quantity: i128) -> i128 {
+        match self {
+            Dispatcher::AscendingPriceAuction => {
+                AscendingPriceAuction.calculate_min_bid(env, auction_data, quantity)
+            }
+            Dispatcher::DescendingPriceAuction => {
+                DescendingPriceAuction.calculate_min_bid(env, auction_data, quantity)
+            }
+            Dispatcher::SecondPriceAuction => {
+                SecondPriceAuction.calculate_min_bid(env, auction_data, quantity)
+            }
+            Dispatcher::UniformPriceAuction => {
+                UniformPriceAuction.calculate_min_bid(env, auction_data, quantity)
+            }
+        }
+    }
+
+    fn validate(&self, settings: &AuctionSettings) -> bool {
+        match self {
+            Dispatcher::AscendingPriceAuction => AscendingPriceAuction.validate(settings),
+            Dispatcher::DescendingPriceAuction => DescendingPriceAuction.validate(settings),
+            Dispatcher::SecondPriceAuction => SecondPriceAuction.validate(settings),
+            Dispatcher::UniformPriceAuction => UniformPriceAuction.validate(settings),
         }
     }
 
@@ -397,17 +1138,20 @@ impl BaseAuction for Dispatcher {
             Dispatcher::DescendingPriceAuction => {
                 DescendingPriceAuction.calculate_price(env, auction_id)
             }
+            Dispatcher::SecondPriceAuction => SecondPriceAuction.calculate_price(env, auction_id),
+            Dispatcher::UniformPriceAuction => UniformPriceAuction.calculate_price(env, auction_id),
         }
     }
 }
 
 #[macro_export]
 macro_rules! dispatcher {
-    ($condition:expr) => {
-        if $condition {
-            Dispatcher::DescendingPriceAuction
-        } else {
-            Dispatcher::AscendingPriceAuction
+    ($settings:expr) => {
+        match $settings.kind {
+            AuctionKind::Ascending | AuctionKind::SealedBid => Dispatcher::AscendingPriceAuction,
+            AuctionKind::Descending => Dispatcher::DescendingPriceAuction,
+            AuctionKind::SecondPrice => Dispatcher::SecondPriceAuction,
+            AuctionKind::MultiUnit => Dispatcher::UniformPriceAuction,
         }
     };
 }
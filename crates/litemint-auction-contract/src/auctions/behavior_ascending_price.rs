@@ -6,44 +6,164 @@
     MIT License
 */
 
-use crate::types::{AuctionData, DataKey};
-use soroban_kit::storage;
-use soroban_sdk::Env;
+use super::behavior::{bid_placed, calculate_share};
+use crate::events::BID;
+use crate::types::{AuctionData, AuctionSettings, DataKey, RESERVE_REVEAL_TIME};
+use soroban_sdk::{symbol_short, Env};
 
 pub struct AscendingPriceAuction;
 
 // AscendingPriceAuction (aka English Auction).
 impl super::behavior::BaseAuction for AscendingPriceAuction {
     fn resolve(&self, env: &Env, auction_id: u64) -> bool {
-        let auction_data =
-            storage::get::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id)).unwrap();
+        let auction_data = AuctionData::load(env, auction_id).unwrap();
+
+        // Sealed bids are not settled before the reveal window closes.
+        if self.is_sealed_bid_auction(&auction_data)
+            && env.ledger().timestamp() <= auction_data.reveal_deadline()
+        {
+            return false;
+        }
 
         // Retrieve the highest bid.
-        if let Some(bid) = auction_data.bids.iter().max_by_key(|bid| bid.amount) {
+        // Earliest bid wins ties (e.g., proxy bid matched at its maximum).
+        let end_time = auction_data.start_time + auction_data.settings.duration;
+        let expired = end_time < env.ledger().timestamp();
+        // Bids in other currencies are ranked by their value in the auction `market`.
+        let settings = &auction_data.settings;
+        let highest = auction_data.bids.iter().reduce(|leader, bid| {
+            if bid.value(env, settings) > leader.value(env, settings) {
+                bid
+            } else {
+                leader
+            }
+        });
+
+        // A hidden reserve must be revealed by the seller before settlement.
+        // If not revealed in time, the highest bid wins regardless of the reserve.
+        if expired
+            && env
+                .storage()
+                .persistent()
+                .has::<DataKey>(&DataKey::HiddenReserve(auction_id))
+        {
+            if env.ledger().timestamp() <= end_time + RESERVE_REVEAL_TIME {
+                return false;
+            }
+            return self.finalize(env, auction_id, highest.as_ref());
+        }
+
+        if let Some(bid) = highest {
             // Check that the reserve is met and
             // either the auction time has expired or the ask price is met.
-            let price = self.calculate_price(&env, auction_id);
-            if bid.amount >= price
-                && (auction_data.start_time + auction_data.settings.duration
-                    < env.ledger().timestamp()
-                    || (auction_data.settings.ask_price > price
-                        && bid.amount >= auction_data.settings.ask_price))
+            let price = self.calculate_price(env, auction_id);
+            let value = bid.value(env, settings);
+            if value >= price
+                && (expired || (settings.ask_price > price && value >= settings.ask_price))
             {
                 return self.finalize(env, auction_id, Some(&bid));
             }
-        } else {
-            // Auction has expired.
-            if auction_data.start_time + auction_data.settings.duration < env.ledger().timestamp() {
-                return self.finalize(env, auction_id, None);
-            }
+        }
+
+        // Auction has expired (no bid or reserve not met).
+        if expired {
+            return self.finalize(env, auction_id, None);
         }
         false
     }
 
+    fn validate(&self, settings: &AuctionSettings) -> bool {
+        // Discount is not supported.
+        settings.discount_percent == 0
+            && settings.discount_frequency == 0
+            && !settings.compounded_discount
+    }
+
+    fn calculate_min_bid(&self, env: &Env, auction_data: &AuctionData, quantity: i128) -> i128 {
+        let reserve = self.calculate_reserve(auction_data, quantity);
+        if self.is_sealed_bid_auction(auction_data) {
+            return reserve;
+        }
+
+        // Open with the starting price, if set, then outbid the leader by the minimum increment.
+        let settings = &auction_data.settings;
+        match auction_data
+            .bids
+            .iter()
+            .map(|bid| bid.value(env, settings))
+            .max()
+        {
+            Some(leader) => outbid(settings, leader),
+            None if settings.starting_price > 0 => settings.starting_price,
+            None => reserve,
+        }
+    }
+
+    // Proxy bidding: the bid with the highest maximum (earliest on ties) leads at the lowest price
+    // outbidding the runner-up, up to its maximum. Outbid proxy bids show their maximum.
+    fn reprice(&self, env: &Env, auction_data: &mut AuctionData) {
+        if self.is_sealed_bid_auction(auction_data) {
+            return;
+        }
+
+        let mut leader: Option<(u32, i128)> = None;
+        let mut runner_up: Option<i128> = None;
+        for (index, bid) in auction_data.bids.iter().enumerate() {
+            match leader {
+                Some((_, max)) if bid.escrow() <= max => {
+                    runner_up = runner_up.max(Some(bid.escrow()));
+                }
+                _ => {
+                    runner_up = leader.map(|(_, max)| max).max(runner_up);
+                    leader = Some((index as u32, bid.escrow()));
+                }
+            }
+        }
+
+        for index in 0..auction_data.bids.len() {
+            let mut bid = auction_data.bids.get_unchecked(index);
+            if bid.max_amount == 0 {
+                continue;
+            }
+            let visible_amount = bid.amount;
+            bid.amount = match leader {
+                Some((lead, _)) if lead == index => {
+                    let price = match runner_up {
+                        Some(runner_up) => outbid(&auction_data.settings, runner_up),
+                        None if auction_data.settings.starting_price > 0 => {
+                            auction_data.settings.starting_price
+                        }
+                        None => auction_data.settings.reserve_price.max(1),
+                    };
+                    price.min(bid.max_amount).max(bid.amount)
+                }
+                _ => bid.max_amount,
+            };
+
+            // New bids are reported when added.
+            if visible_amount > 0 && bid.amount != visible_amount {
+                env.events().publish(
+                    (BID, symbol_short!("repriced")),
+                    bid_placed(auction_data.id, &bid),
+                );
+            }
+            auction_data.bids.set(index, bid);
+        }
+    }
+
     fn calculate_price(&self, env: &Env, auction_id: u64) -> i128 {
-        storage::get::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id))
+        AuctionData::load(env, auction_id)
             .unwrap()
             .settings
             .reserve_price
     }
 }
+
+// Lowest amount outbidding `leader` by the minimum increment.
+fn outbid(settings: &AuctionSettings, leader: i128) -> i128 {
+    let increment = match settings.relative_increment {
+        true => calculate_share(leader, settings.min_increment),
+        false => settings.min_increment,
+    };
+    leader.checked_add(increment.max(1)).unwrap()
+}
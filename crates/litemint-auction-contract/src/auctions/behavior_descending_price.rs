@@ -6,8 +6,7 @@
     MIT License
 */
 
-use crate::types::{AuctionData, DataKey};
-use soroban_kit::storage;
+use crate::types::{AuctionData, AuctionSettings};
 use soroban_sdk::Env;
 
 pub struct DescendingPriceAuction;
@@ -15,8 +14,7 @@ pub struct DescendingPriceAuction;
 // DescendingPriceAuction (aka Dutch Auction).
 impl super::behavior::BaseAuction for DescendingPriceAuction {
     fn resolve(&self, env: &Env, auction_id: u64) -> bool {
-        let auction_data =
-            storage::get::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id)).unwrap();
+        let auction_data = AuctionData::load(env, auction_id).unwrap();
 
         // Auction has expired.
         if auction_data.start_time + auction_data.settings.duration < env.ledger().timestamp() {
@@ -33,15 +31,25 @@ impl super::behavior::BaseAuction for DescendingPriceAuction {
         }
     }
 
+    fn validate(&self, settings: &AuctionSettings) -> bool {
+        // Discount must apply to the ask price.
+        settings.discount_percent > 0
+            && settings.discount_percent <= 100
+            && settings.discount_frequency > 0
+            && settings.ask_price > 0
+    }
+
     fn calculate_price(&self, env: &Env, auction_id: u64) -> i128 {
-        let auction_data =
-            storage::get::<DataKey, AuctionData>(env, &DataKey::AuctionData(auction_id)).unwrap();
+        let auction_data = AuctionData::load(env, auction_id).unwrap();
         assert!(
             auction_data.settings.discount_percent > 0
                 && auction_data.settings.discount_frequency > 0
         );
 
-        let elapsed = env.ledger().timestamp() - auction_data.start_time;
+        let elapsed = env
+            .ledger()
+            .timestamp()
+            .saturating_sub(auction_data.start_time);
         let periods = elapsed / auction_data.settings.discount_frequency;
         if auction_data.settings.compounded_discount {
             // Apply compound discount.
@@ -0,0 +1,72 @@
+/*
+    Date: 2023
+    Author: Fred Kyung-jin Rezeau <fred@litemint.com>
+    Copyright (c) 2023 Litemint LLC
+
+    MIT License
+*/
+
+use crate::types::{AuctionData, AuctionSettings, BidData};
+use soroban_sdk::Env;
+
+pub struct SecondPriceAuction;
+
+// SecondPriceAuction (aka Vickrey Auction).
+impl super::behavior::BaseAuction for SecondPriceAuction {
+    fn resolve(&self, env: &Env, auction_id: u64) -> bool {
+        let auction_data = AuctionData::load(env, auction_id).unwrap();
+
+        // Sealed bids are only settled once the auction time has expired.
+        if auction_data.start_time + auction_data.settings.duration >= env.ledger().timestamp() {
+            return false;
+        }
+
+        // Retrieve the highest bid.
+        // Earliest bid wins ties.
+        let price = self.calculate_price(env, auction_id);
+        let highest = auction_data.bids.iter().reduce(|leader, bid| {
+            if bid.amount > leader.amount {
+                bid
+            } else {
+                leader
+            }
+        });
+        match highest {
+            Some(bid) if bid.amount >= price => {
+                // The winner pays the second highest bid or the reserve price.
+                let second_price = auction_data
+                    .bids
+                    .iter()
+                    .filter(|b| b.buyer != bid.buyer)
+                    .map(|b| b.amount)
+                    .max()
+                    .unwrap_or(price)
+                    .max(price);
+                self.finalize(
+                    env,
+                    auction_id,
+                    Some(&BidData {
+                        amount: second_price,
+                        ..bid
+                    }),
+                )
+            }
+            _ => self.finalize(env, auction_id, None),
+        }
+    }
+
+    fn validate(&self, settings: &AuctionSettings) -> bool {
+        // Discount and ask price (buy now) are not supported.
+        settings.discount_percent == 0
+            && settings.discount_frequency == 0
+            && !settings.compounded_discount
+            && settings.ask_price == 0
+    }
+
+    fn calculate_price(&self, env: &Env, auction_id: u64) -> i128 {
+        AuctionData::load(env, auction_id)
+            .unwrap()
+            .settings
+            .reserve_price
+    }
+}
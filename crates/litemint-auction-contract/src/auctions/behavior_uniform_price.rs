@@ -0,0 +1,86 @@
+/*
+    Date: 2023
+    Author: Fred Kyung-jin Rezeau <fred@litemint.com>
+    Copyright (c) 2023 Litemint LLC
+
+    MIT License
+*/
+
+use crate::types::{AuctionData, AuctionSettings, BidData};
+use soroban_sdk::{Env, Vec};
+
+pub struct UniformPriceAuction;
+
+// UniformPriceAuction (aka uniform price multi-unit auction).
+impl super::behavior::BaseAuction for UniformPriceAuction {
+    fn resolve(&self, env: &Env, auction_id: u64) -> bool {
+        let auction_data = AuctionData::load(env, auction_id).unwrap();
+
+        // Bids are only settled once the auction time has expired.
+        if auction_data.start_time + auction_data.settings.duration >= env.ledger().timestamp() {
+            return false;
+        }
+
+        // Rank bids by unit price, earlier bids win ties.
+        let mut ranked: Vec<BidData> = Vec::new(env);
+        for bid in auction_data.bids.iter().filter(|b| b.quantity > 0) {
+            let unit_price = bid.amount / bid.quantity;
+            let index = ranked
+                .iter()
+                .position(|b| b.amount / b.quantity < unit_price)
+                .unwrap_or(ranked.len() as usize);
+            ranked.insert(index as u32, bid);
+        }
+
+        // Fill the lot from the highest unit price down.
+        let mut remaining = auction_data.settings.amount;
+        let mut winners: Vec<BidData> = Vec::new(env);
+        let mut clearing_price = 0;
+        for bid in ranked.iter() {
+            if remaining == 0 {
+                break;
+            }
+            let quantity = bid.quantity.min(remaining);
+            remaining -= quantity;
+            clearing_price = bid.amount / bid.quantity;
+            winners.push_back(BidData { quantity, ..bid });
+        }
+
+        if winners.is_empty() {
+            return self.finalize(env, auction_id, None);
+        }
+
+        // All winners pay the lowest accepted unit price.
+        for i in 0..winners.len() {
+            let mut bid = winners.get_unchecked(i);
+            bid.amount = clearing_price.checked_mul(bid.quantity).unwrap();
+            winners.set(i, bid);
+        }
+        self.finalize_winners(env, auction_id, &winners)
+    }
+
+    fn validate(&self, settings: &AuctionSettings) -> bool {
+        // Discount and ask price (buy now) are not supported.
+        settings.discount_percent == 0
+            && settings.discount_frequency == 0
+            && !settings.compounded_discount
+            && settings.ask_price == 0
+    }
+
+    fn calculate_price(&self, env: &Env, auction_id: u64) -> i128 {
+        AuctionData::load(env, auction_id)
+            .unwrap()
+            .settings
+            .reserve_price
+    }
+
+    fn calculate_reserve(&self, auction_data: &AuctionData, quantity: i128) -> i128 {
+        // Reserve price applies per unit.
+        assert!(quantity > 0 && quantity <= auction_data.settings.amount);
+        auction_data
+            .settings
+            .reserve_price
+            .checked_mul(quantity)
+            .unwrap()
+    }
+}
@@ -15,6 +15,10 @@
 //! - Descending price auctions (see: behavior_descending_price.rs) supporting linear
 //!   or compound discount, and customizable frequency/rate.
 //! - Ascending price auctions (see: behavior_ascending_price.rs) with "buy now" option.
+//! - Second price sealed bid auctions (see: behavior_second_price.rs) where the highest
+//!   bidder pays the second highest bid.
+//! - Uniform price multi-unit auctions (see: behavior_uniform_price.rs) where fungible lots
+//!   are allocated to the highest unit price bids at a single clearing price.
 //! - Reserve price.
 //! - Anti-snipe mechanism.
 //! - Concurrent and cancellable bids.
@@ -22,3 +26,5 @@
 pub(crate) mod behavior;
 pub(crate) mod behavior_ascending_price;
 pub(crate) mod behavior_descending_price;
+pub(crate) mod behavior_second_price;
+pub(crate) mod behavior_uniform_price;
@@ -0,0 +1,214 @@
+/*
+    Date: 2023
+    Author: Fred Kyung-jin Rezeau <fred@litemint.com>
+    Copyright (c) 2023 Litemint LLC
+
+    MIT License
+*/
+
+//! Contract events.
+//! Event data are versioned `contracttype` structs carrying the full state change,
+//! so that indexers can rebuild the off-chain state from events alone.
+
+use soroban_sdk::{contracttype, symbol_short, Address, Symbol, Vec};
+
+use crate::types::{AuctionKind, Role};
+
+// Version of the event data, incremented on breaking changes.
+pub const EVENTS_VERSION: u32 = 1;
+
+// Event topics.
+pub const AUCTION: Symbol = symbol_short!("AUCTION");
+pub const BID: Symbol = symbol_short!("BID");
+pub const ADMIN: Symbol = symbol_short!("ADMIN");
+
+// Transfer from the contract to `recipient`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Payment {
+    pub recipient: Address,
+    pub market: Address,
+    pub amount: i128,
+}
+
+// (AUCTION, "started")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionStarted {
+    pub version: u32,
+    pub auction_id: u64,
+    pub kind: AuctionKind,
+    pub seller: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub market: Address,
+    pub start_time: u64,
+    pub end_time: u64,
+}
+
+// (BID, "sealed")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidSealed {
+    pub version: u32,
+    pub auction_id: u64,
+    pub buyer: Address,
+    pub market: Address,
+    pub deposit: i128,
+}
+
+// (BID, "added"), (BID, "raised") and (BID, "repriced") when a proxy bid visible amount changes.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidPlaced {
+    pub version: u32,
+    pub auction_id: u64,
+    pub buyer: Address,
+    pub market: Address,
+    pub amount: i128,
+    pub max_amount: i128,
+    pub quantity: i128,
+    pub sniper: bool,
+}
+
+// (BID, "deleted") for cancelled bids and (BID, "refunded") for revealed deposits.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BidRefunded {
+    pub version: u32,
+    pub auction_id: u64,
+    pub refund: Payment,
+}
+
+// (BID, "extended") on soft-close, (AUCTION, "extended") when extended by the seller.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionExtended {
+    pub version: u32,
+    pub auction_id: u64,
+    pub end_time: u64,
+    pub extensions: u32,
+}
+
+// (BID, "forfeited")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct DepositsForfeited {
+    pub version: u32,
+    pub auction_id: u64,
+    pub market: Address,
+    pub total: i128,
+    pub admin_share: i128,
+    pub seller_share: i128,
+}
+
+// (AUCTION, "reserve")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ReserveRevealed {
+    pub version: u32,
+    pub auction_id: u64,
+    pub reserve: i128,
+}
+
+// Payout of a winning bid, in the currency of the bid.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Settlement {
+    pub buyer: Address,
+    pub market: Address,
+    pub quantity: i128,
+    pub amount: i128,
+    pub admin_share: i128,
+    pub royalty: i128,
+    pub seller_share: i128,
+}
+
+// (AUCTION, "skipped") when the royalty due by `buyer` cannot be paid from the winning bid.
+// `interest` is the royalty quoted for the buyer, as (amount, currency) pairs.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoyaltySkipped {
+    pub version: u32,
+    pub auction_id: u64,
+    pub property: Address,
+    pub buyer: Address,
+    pub interest: Vec<(i128, Address)>,
+}
+
+// (AUCTION, "won") with winners, (AUCTION, "ended") without.
+// `clearing_price` is the winning amount, or the unit price paid by all winners of `MultiUnit`
+// auctions, zero without winners.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionSettled {
+    pub version: u32,
+    pub auction_id: u64,
+    pub winners: Vec<Settlement>,
+    pub clearing_price: i128,
+    pub refunds: Vec<Payment>,
+}
+
+// (AUCTION, "cancelled")
+// `penalty` is paid by the seller to the treasury, in the auction `market`.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionCancelled {
+    pub version: u32,
+    pub auction_id: u64,
+    pub penalty: i128,
+    pub refunds: Vec<Payment>,
+}
+
+// (ADMIN, "rate")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommissionRateUpdated {
+    pub version: u32,
+    pub old_rate: i128,
+    pub new_rate: i128,
+}
+
+// (ADMIN, "antisnipe")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AntiSnipeTimeUpdated {
+    pub version: u32,
+    pub old_time: u64,
+    pub new_time: u64,
+}
+
+// (ADMIN, "extend")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExtendableAuctionsUpdated {
+    pub version: u32,
+    pub extendable_auctions: bool,
+}
+
+// (ADMIN, "proposed")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminProposed {
+    pub version: u32,
+    pub admin: Address,
+    pub proposed_admin: Address,
+}
+
+// (ADMIN, "transfer")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransferred {
+    pub version: u32,
+    pub old_admin: Address,
+    pub new_admin: Address,
+}
+
+// (ADMIN, "granted") and (ADMIN, "revoked")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleUpdated {
+    pub version: u32,
+    pub role: Role,
+    pub account: Address,
+}
@@ -0,0 +1,226 @@
+/*
+    Date: 2023
+    Author: Fred Kyung-jin Rezeau <fred@litemint.com>
+    Copyright (c) 2023 Litemint LLC
+
+    MIT License
+*/
+
+//! Indexes of active auctions (all, by seller, by token and by market),
+//! allowing clients to discover auctions on chain.
+//!
+//! Each index is a doubly linked list held in persistent storage, one entry
+//! per auction, so that indexes grow without bound on a single entry size.
+//! Pages are retrieved after a given auction id, which remains a valid cursor
+//! while entries are added or removed.
+//!
+//! Entries live as long as their auction or their neighbors, whichever is extended last.
+//! Entries of abandoned auctions may still expire, the list then ends at the gap.
+
+use soroban_sdk::{vec, Env, Vec};
+
+use crate::auctions::behavior::ledger_times::LEDGERS_PER_DAY;
+use crate::types::{AuctionData, AuctionIndex, DataKey, IndexBounds, IndexNode};
+
+// Maximum number of auctions returned per page.
+pub const MAX_PAGE_SIZE: u32 = 50;
+
+// Time to live of removed entries (in ledgers), allowing cursors on them to resume.
+const REMOVED_NODE_TTL: u32 = LEDGERS_PER_DAY as u32;
+
+// Auctions are indexed under every market they accept.
+fn indexes(env: &Env, auction_data: &AuctionData) -> Vec<AuctionIndex> {
+    let mut indexes = vec![
+        env,
+        AuctionIndex::Active,
+        AuctionIndex::Seller(auction_data.settings.seller.clone()),
+        AuctionIndex::Token(auction_data.settings.token.clone()),
+        AuctionIndex::Market(auction_data.settings.market.clone()),
+    ];
+    for market in auction_data.settings.markets.iter() {
+        let index = AuctionIndex::Market(market);
+        if !indexes.contains(&index) {
+            indexes.push_back(index);
+        }
+    }
+    indexes
+}
+
+fn node(env: &Env, index: &AuctionIndex, id: u64) -> Option<IndexNode> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, IndexNode>(&DataKey::IndexNode(index.clone(), id))
+}
+
+fn set_node(env: &Env, index: &AuctionIndex, id: u64, node: &IndexNode) {
+    env.storage()
+        .persistent()
+        .set::<DataKey, IndexNode>(&DataKey::IndexNode(index.clone(), id), node);
+}
+
+// Append the auction to all its indexes.
+pub fn add(env: &Env, auction_data: &AuctionData) {
+    let id = auction_data.id;
+    for index in indexes(env, auction_data) {
+        let key = DataKey::Index(index.clone());
+        let bounds = env.storage().persistent().get::<DataKey, IndexBounds>(&key);
+        // An expired tail starts the index over.
+        let head = match bounds
+            .and_then(|bounds| node(env, &index, bounds.tail).map(|tail_node| (bounds, tail_node)))
+        {
+            Some((bounds, mut tail_node)) => {
+                tail_node.next = Some(id);
+                set_node(env, &index, bounds.tail, &tail_node);
+                set_node(
+                    env,
+                    &index,
+                    id,
+                    &IndexNode {
+                        prev: Some(bounds.tail),
+                        next: None,
+                    },
+                );
+                bounds.head
+            }
+            None => {
+                set_node(
+                    env,
+                    &index,
+                    id,
+                    &IndexNode {
+                        prev: None,
+                        next: None,
+                    },
+                );
+                id
+            }
+        };
+        env.storage()
+            .persistent()
+            .set::<DataKey, IndexBounds>(&key, &IndexBounds { head, tail: id });
+    }
+}
+
+// Unlink the auction from all its indexes.
+// The removed entry is kept temporarily so that pages can resume after it.
+pub fn remove(env: &Env, auction_data: &AuctionData) {
+    let id = auction_data.id;
+    for index in indexes(env, auction_data) {
+        let Some(removed) = node(env, &index, id) else {
+            continue;
+        };
+        // Expired neighbors are left unlinked.
+        if let Some(prev) = removed.prev {
+            if let Some(mut prev_node) = node(env, &index, prev) {
+                prev_node.next = removed.next;
+                set_node(env, &index, prev, &prev_node);
+            }
+        }
+        if let Some(next) = removed.next {
+            if let Some(mut next_node) = node(env, &index, next) {
+                next_node.prev = removed.prev;
+                set_node(env, &index, next, &next_node);
+            }
+        }
+
+        // Bounds are left untouched by entries no longer reachable (see `add`).
+        let key = DataKey::Index(index.clone());
+        if let Some(mut bounds) = env.storage().persistent().get::<DataKey, IndexBounds>(&key) {
+            match (bounds.head == id, bounds.tail == id) {
+                (true, true) => env.storage().persistent().remove::<DataKey>(&key),
+                (head, tail) => {
+                    if head {
+                        bounds.head = removed.next.unwrap_or_default();
+                    }
+                    if tail {
+                        bounds.tail = removed.prev.unwrap_or_default();
+                    }
+                    env.storage()
+                        .persistent()
+                        .set::<DataKey, IndexBounds>(&key, &bounds);
+                }
+            }
+        }
+
+        let node_key = DataKey::IndexNode(index, id);
+        env.storage().persistent().remove::<DataKey>(&node_key);
+        env.storage()
+            .temporary()
+            .set::<DataKey, IndexNode>(&node_key, &removed);
+        env.storage()
+            .temporary()
+            .extend_ttl(&node_key, REMOVED_NODE_TTL, REMOVED_NODE_TTL);
+    }
+}
+
+// Extend the time to live of the auction entries in all its indexes, of their neighbors,
+// and of the indexes themselves.
+pub fn extend_ttl(env: &Env, auction_data: &AuctionData, ledgers: u32) {
+    let persistent = env.storage().persistent();
+    for index in indexes(env, auction_data) {
+        let Some(current) = node(env, &index, auction_data.id) else {
+            continue;
+        };
+        let mut keys = vec![
+            env,
+            DataKey::IndexNode(index.clone(), auction_data.id),
+            DataKey::Index(index.clone()),
+        ];
+        for id in [current.prev, current.next].into_iter().flatten() {
+            keys.push_back(DataKey::IndexNode(index.clone(), id));
+        }
+        for key in keys.iter() {
+            if persistent.has::<DataKey>(&key) {
+                persistent.extend_ttl::<DataKey>(&key, ledgers, ledgers);
+            }
+        }
+    }
+}
+
+fn head(env: &Env, index: &AuctionIndex) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get::<DataKey, IndexBounds>(&DataKey::Index(index.clone()))
+        .map(|bounds| bounds.head)
+}
+
+// First auction in the index after `cursor` (or the first one).
+// Removed entries are resolved through their neighbors at the time of removal.
+fn first_after(env: &Env, index: &AuctionIndex, cursor: Option<u64>) -> Option<u64> {
+    let Some(mut id) = cursor else {
+        return head(env, index);
+    };
+    loop {
+        if let Some(node) = node(env, index, id) {
+            return node.next;
+        }
+        let removed = env
+            .storage()
+            .temporary()
+            .get::<DataKey, IndexNode>(&DataKey::IndexNode(index.clone(), id))
+            .expect("Invalid cursor");
+        match (removed.next, removed.prev) {
+            (Some(next), _) if node(env, index, next).is_some() => return Some(next),
+            (Some(next), _) => id = next,
+            (None, Some(prev)) => id = prev,
+            (None, None) => return head(env, index),
+        }
+    }
+}
+
+// Retrieve up to `limit` auctions from the index, after auction `cursor` (or from the start).
+// Expired auctions are skipped, and the page ends at expired entries.
+pub fn page(env: &Env, index: &AuctionIndex, cursor: Option<u64>, limit: u32) -> Vec<AuctionData> {
+    let mut auctions = Vec::new(env);
+    let mut id = first_after(env, index, cursor);
+    while let Some(current) = id.filter(|_| auctions.len() < limit.min(MAX_PAGE_SIZE)) {
+        let Some(current_node) = node(env, index, current) else {
+            break;
+        };
+        if let Some(auction_data) = AuctionData::load(env, current) {
+            auctions.push_back(auction_data);
+        }
+        id = current_node.next;
+    }
+    auctions
+}
@@ -12,16 +12,30 @@
 #![no_std]
 
 mod auctions; // Auction behaviors and mechanisms.
+mod events; // Contract events.
+mod index; // Active auction indexes.
+mod royalty; // Royalty contract client.
 mod types; // Contract types.
 
 use soroban_kit::{
     fsm::{self, StateMachine},
-    storage,
+    oracle, oracle_subscriber, soroban_tools, storage, when_closed, when_opened, CircuitBreaker,
+    TransitionHandler,
+};
+use soroban_sdk::{
+    contract, contractimpl, contractmeta, symbol_short, vec, Address, BytesN, Env, Vec,
 };
-use soroban_sdk::{contract, contractimpl, contractmeta, vec, Address, BytesN, Env, Vec};
 
-use crate::auctions::{behavior::BaseAuction, behavior::Dispatcher};
-use types::{AdminData, AuctionData, AuctionPhase, AuctionRegion, AuctionSettings, DataKey};
+use crate::auctions::behavior::{extend_instance_ttl, BaseAuction, Dispatcher, OUTCOME_TTL};
+use crate::events::{
+    AdminProposed, AdminTransferred, AntiSnipeTimeUpdated, AuctionExtended, CommissionRateUpdated,
+    ExtendableAuctionsUpdated, RoleUpdated, ADMIN, AUCTION, EVENTS_VERSION,
+};
+use types::{
+    migrate_entry, AdminData, AuctionData, AuctionIndex, AuctionKind, AuctionOutcome, AuctionPhase,
+    AuctionRegion, AuctionSettings, CancellationPolicy, DataKey, MarketData, MarketDataKey, Role,
+    SoftClosePolicy, MAX_ANTI_SNIPE_TIME, MAX_BPS,
+};
 
 contractmeta!(
     key = "desc",
@@ -30,46 +44,281 @@ contractmeta!(
 
 pub trait AuctionContractTrait {
     // Upgrade this contract.
-    // Admin authorization required.
+    // Upgrader authorization required.
     fn upgrade(e: Env, wasm_hash: BytesN<32>);
 
+    // Move legacy auctions (held in instance storage) to persistent storage.
+    // Active auctions are also moved when first accessed, ended auctions only keep their phase.
+    // Upgrader authorization required.
+    fn migrate(env: Env, auction_ids: Vec<u64>);
+
     // Retrieves auction data for an existing auction.
     // No authorization required.
     fn get_auction(env: Env, auction_id: u64) -> Option<AuctionData>;
 
+    // Retrieve the minimum acceptable bid amount for an existing auction, in the auction `market`.
+    // For ascending auctions, this is the starting price or the leading bid plus the minimum increment.
+    // No authorization required.
+    fn get_min_bid(env: Env, auction_id: u64) -> i128;
+
+    // Retrieve active auctions, up to `limit` (max 50) after auction `cursor`
+    // (from the first one if `None`).
+    // No authorization required.
+    fn list_auctions(env: Env, cursor: Option<u64>, limit: u32) -> Vec<AuctionData>;
+
+    // Retrieve active auctions by seller, up to `limit` (max 50) after auction `cursor`
+    // (from the first one if `None`).
+    // No authorization required.
+    fn auctions_by_seller(
+        env: Env,
+        seller: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Vec<AuctionData>;
+
+    // Retrieve active auctions by token, up to `limit` (max 50) after auction `cursor`
+    // (from the first one if `None`).
+    // No authorization required.
+    fn auctions_by_token(
+        env: Env,
+        token: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Vec<AuctionData>;
+
+    // Retrieve active auctions by market, up to `limit` (max 50) after auction `cursor`
+    // (from the first one if `None`).
+    // No authorization required.
+    fn auctions_by_market(
+        env: Env,
+        market: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Vec<AuctionData>;
+
+    // Retrieve the current phase of an auction, `None` if unknown.
+    // Active auctions are `Scheduled`, `Committing` or `Revealing` (sealed bid auctions), or `Running`.
+    // Ended auctions are `Settled` or `Cancelled`.
+    // No authorization required.
+    fn get_phase(env: Env, auction_id: u64) -> Option<AuctionPhase>;
+
+    // Apply the time-based phase transitions of an active auction and return its phase.
+    // Transitions are also applied by the guarded operations.
+    // No authorization required.
+    fn advance(env: Env, auction_id: u64) -> AuctionPhase;
+
+    // Retrieve the archived outcome of a settled or cancelled auction, `None` if still active or unknown.
+    // Outcomes are kept in persistent storage for about 90 days after settlement.
+    // No authorization required.
+    fn get_outcome(env: Env, auction_id: u64) -> Option<AuctionOutcome>;
+
     // Resolves the auction.
+    // Return the outcome once settled (see `get_outcome`), `None` while still active.
     // No authorization required.
-    fn resolve(env: Env, auction_id: u64);
+    fn resolve(env: Env, auction_id: u64) -> Option<AuctionOutcome>;
 
     // Place a sealed bid.
-    // Require auction to be in `commit` phase.
-    // Bid amount must be sealed using `sha256([big_endian_amount;16][salt;32][big_endian_auction_id;8])`.
+    // Require auction to be in `Committing` phase.
+    // Bid amount must be sealed using
+    // `sha256([big_endian_amount;16][salt;32][big_endian_auction_id;8][buyer_address_xdr])`.
+    // One sealed bid per buyer, only revealed by the buyer who committed it.
     // Buyer authorization required.
     fn place_sealed_bid(env: Env, auction_id: u64, buyer: Address, sealed_amount: BytesN<32>);
 
     // Place or reveal a bid.
-    // Late bids (i.e., within anti_snipe_time from the end of the auction)
-    // are subject to anti-snipe rules and cannot be cancelled or modified.
+    // Require auction to be in `Running` phase, or `Revealing` phase to reveal sealed bids.
+    // Late bids (i.e., within the soft-close window from the end of the auction)
+    // are subject to anti-snipe rules and cannot be cancelled (see `raise_bid`).
     // Buyer authorization required.
     fn place_bid(env: Env, auction_id: u64, buyer: Address, amount: i128, salt: Option<BytesN<32>>);
 
+    // Place a bid in one of the currencies accepted by the auction (`market` or `markets`).
+    // Bids are ranked by their value in the auction `market`, using the oracle price feeds
+    // (see `allow_broker`). The winner pays in the currency of the bid, other bids are refunded
+    // in the currency they escrowed.
+    // Require auction to be in `Running` phase.
+    // Buyer authorization required.
+    fn place_bid_with_market(
+        env: Env,
+        auction_id: u64,
+        buyer: Address,
+        market: Address,
+        amount: i128,
+    );
+
+    // Place a proxy bid, escrowing up to `max_amount`.
+    // Require an `Ascending` auction accepting a single currency. The contract bids on behalf of the buyer, only as much as needed
+    // to outbid the runner-up by the minimum increment, up to `max_amount`.
+    // The winner pays the visible price and the unused escrow is refunded on settlement.
+    // Buyer authorization required.
+    fn place_proxy_bid(env: Env, auction_id: u64, buyer: Address, max_amount: i128);
+
+    // Raise an existing bid to `amount`, escrowing only the difference.
+    // Allowed within the anti-snipe window (including for late bids), in which case
+    // the auction is extended again. Raises the maximum of proxy bids.
+    // Not available for sealed bid auctions.
+    // Buyer authorization required.
+    fn raise_bid(env: Env, auction_id: u64, buyer: Address, amount: i128);
+
+    // Place a bid for `quantity` units at `price` per unit.
+    // Require a `MultiUnit` auction, a zero `quantity` cancels the existing bid.
+    // The bid is escrowed in full (`quantity * price`) and the unused part refunded on settlement.
+    // Buyer authorization required.
+    fn place_multi_unit_bid(env: Env, auction_id: u64, buyer: Address, quantity: i128, price: i128);
+
     // Extend the duration of an ongoing auction.
     // Require admin settings `extendable_auctions` set to true.
     // Seller authorization required.
     fn extend(env: Env, auction_id: u64, duration: u64) -> bool;
 
+    // Cancel an auction.
+    // The token is returned to the seller, all bids and sealed bid deposits are refunded.
+    // Subject to the cancellation policy once a bid meets the reserve price.
+    // Ended auctions cannot be cancelled and must be resolved.
+    // Seller authorization required.
+    fn cancel(env: Env, auction_id: u64);
+
+    // Set the cancellation policy.
+    // By default, auctions with a bid meeting the reserve price cannot be cancelled.
+    // If `allow_with_bids` is true, the seller pays `penalty_rate` (basis points of the highest bid)
+    // to the treasury.
+    // Config operator authorization required.
+    fn set_cancellation_policy(env: Env, policy: CancellationPolicy);
+
+    // Set the share of forfeited sealed bid deposits paid to the treasury, in basis points.
+    // Deposits not revealed by the end of the reveal window are forfeited when the auction ends,
+    // the seller receives the remainder (all of it by default).
+    // Config operator authorization required.
+    fn set_forfeiture_rate(env: Env, rate: i128);
+
+    // Set the limits for the auctions soft-close policy (`window`, `extension` and `max_extensions`).
+    // By default, limited to `anti_snipe_time` for both window and extension, with unlimited extensions.
+    // Config operator authorization required.
+    fn set_soft_close_limits(env: Env, limits: SoftClosePolicy);
+
+    // Pause the contract (circuit breaker).
+    // Blocks `start`, `place_bid`, `place_bid_with_market`, `place_proxy_bid`, `raise_bid`,
+    // `place_multi_unit_bid` and `place_sealed_bid` until unpaused.
+    // `resolve`, bid cancellations and sealed bid reveals remain available so that no funds
+    // get stuck or forfeited.
+    // Pauser authorization required.
+    fn pause(env: Env);
+
+    // Unpause the contract.
+    // Pauser authorization required.
+    fn unpause(env: Env);
+
     // Start a new auction.
     // Return the new `auction_id`.
-    // - Behaves as descending price auction if both `discount_percent` and `discount_frequency` have non-zero values.
-    // - Enters `commit` phase for sealed bids if both `sealed_phase_time` and `sealed_bid_deposit` are specified.
+    // The auction behavior is selected by `kind`:
+    // - `Ascending`: open ascending price auction.
+    // - `Descending`: open descending price auction, requires `discount_percent`, `discount_frequency`
+    //   and `ask_price` to be set.
+    // - `SealedBid`: ascending price auction entering `Committing` phase for sealed bids.
+    // - `SecondPrice`: sealed bid auction where the highest bidder wins and pays the second highest bid
+    //   or the `reserve_price`.
+    // - `MultiUnit`: uniform price auction for fungible lots, `amount` units are allocated to the
+    //   highest unit price bids and all winners pay the lowest accepted unit price.
+    //   The `reserve_price` applies per unit.
     // Notes:
-    // - When in `running` phase, the auction can be instantly won if a bidder meets or exceeds the `ask_price`,
+    // - `SealedBid` and `SecondPrice` require `sealed_phase_time`, `sealed_bid_deposit` and
+    //   `reveal_phase_time`, other kinds require them unset. Bids must be revealed within `reveal_phase_time`
+    //   after the commit phase (ending within `duration`), the winner is not decided before then and
+    //   unrevealed deposits are forfeited (see `set_forfeiture_rate`).
+    // - `Ascending` auctions accept an optional `starting_price` for the opening bid (may be below the
+    //   `reserve_price`) and a `min_increment` over the leading bid, in basis points if `relative_increment`
+    //   is true. Other kinds require them unset.
+    // - `soft_close` sets the anti-snipe policy within the admin limits: bids placed within `window`
+    //   from the end extend the auction by `extension`, up to `max_extensions` times.
+    //   A zero `window` applies the admin default (`anti_snipe_time`).
+    // - `Ascending` auctions may accept bids in additional currencies (`markets`), normalized to `market`
+    //   using the oracle price feeds. Other kinds require `markets` empty.
+    // - `start_time` schedules the auction in the future (zero to start immediately). The token is
+    //   escrowed right away, bids and sealed bids are refused until then (`Scheduled` phase),
+    //   `duration` and discounts are measured from `start_time`.
+    // - When in `Running` phase, the auction can be instantly won if a bidder meets or exceeds the `ask_price`,
     //   provided it is set above the `reserve_price` or discounted below the bid amount for descending auctions.
-    // - `discount_percent` and `discount_frequency` are ignored for sealed bid auctions.
+    // - Settings not supported by the auction kind (e.g., discount for ascending auctions, `ask_price`
+    //   for second price auctions) are rejected.
     // Seller authorization required.
     fn start(env: Env, auction_settings: AuctionSettings) -> u64;
 
+    // Start a new `Ascending` auction with a hidden reserve price.
+    // `reserve_hash` commits to the reserve using `sha256([big_endian_reserve;16][salt;32])`,
+    // `reserve_price` must be unset. Bids are settled once the seller reveals the reserve
+    // (see `reveal_reserve`), or regardless of the reserve if not revealed in time.
+    // Seller authorization required.
+    fn start_with_hidden_reserve(
+        env: Env,
+        auction_settings: AuctionSettings,
+        reserve_hash: BytesN<32>,
+    ) -> u64;
+
+    // Reveal the hidden reserve price and resolve the auction.
+    // Require the auction to have ended, within one hour of the end time.
+    // Seller authorization required.
+    fn reveal_reserve(env: Env, auction_id: u64, reserve: i128, salt: BytesN<32>);
+
+    // Allow an oracle broker to publish price feeds to this contract.
+    // Feeds are indexed by market (topic), priced in the auction `market` with 7 decimals.
+    // Broker manager authorization required.
+    fn allow_broker(env: Env, broker: Address);
+
+    // Deny an oracle broker.
+    // Broker manager authorization required.
+    fn deny_broker(env: Env, broker: Address);
+
+    // Set the maximum age of oracle prices (in seconds, 3600 by default).
+    // Bids in other currencies are not accepted with older prices, bids already placed
+    // are ranked and settled at the last known price.
+    // Config operator authorization required.
+    fn set_max_price_age(env: Env, max_age: u64);
+
+    // Set the marketplace commission rate, in basis points (e.g., 250 for 2.5%).
+    // Commissions are paid to the treasury.
+    // Config operator authorization required.
+    fn set_commission_rate(env: Env, commission_rate: i128);
+
+    // Set the default anti-snipe time (in seconds, max 60), see `set_soft_close_limits`.
+    // Config operator authorization required.
+    fn set_anti_snipe_time(env: Env, anti_snipe_time: u64);
+
+    // Allow or deny sellers to extend their auctions (see `extend`).
+    // Config operator authorization required.
+    fn set_extendable_auctions(env: Env, extendable_auctions: bool);
+
+    // Propose a new admin, replacing any pending proposal.
+    // Admin rights are only transferred once accepted (see `accept_admin`).
+    // Admin authorization required.
+    fn propose_admin(env: Env, new_admin: Address);
+
+    // Accept the admin rights proposed to the caller.
+    // Proposed admin authorization required.
+    fn accept_admin(env: Env);
+
+    // Grant `role` to `account`, replacing the current holder.
+    // Roles not granted are held by the admin.
+    // Admin authorization required.
+    fn grant_role(env: Env, role: Role, account: Address);
+
+    // Revoke `role`, returning it to the admin.
+    // Admin authorization required.
+    fn revoke_role(env: Env, role: Role);
+
+    // Retrieve the account holding `role`.
+    // No authorization required.
+    fn get_role(env: Env, role: Role) -> Address;
+
+    // Set the royalty contract (see: litemint-royalty-contract), `None` to disable.
+    // When set, royalties due on the auctioned token are paid out of the winning bid
+    // and the license is marked as paid by the buyer. Settlement fails (bids remain in escrow)
+    // while the royalty cannot be paid from the winning bid, e.g., royalty contract paused.
+    // Stored apart from the admin data, so that the `AdminData` layout is unchanged.
+    // Config operator authorization required.
+    fn set_royalty_contract(env: Env, royalty_contract: Option<Address>);
+
     // Contract administration.
+    // `commission_rate` is expressed in basis points (e.g., 250 for 2.5%).
     // Admin authorization required.
     fn initialize(
         env: Env,
@@ -84,26 +333,134 @@ pub trait AuctionContractTrait {
 }
 
 #[contract]
+#[oracle_subscriber(Address, MarketData)]
 struct AuctionContract;
 
+// Circuit breaker for the pausable contract operations.
+#[derive(CircuitBreaker)]
+struct AuctionCircuit;
+
+impl AuctionCircuit {
+    #[when_closed(region = "AuctionRegion:Circuit")]
+    fn require_closed(&self, env: &Env) {}
+
+    #[when_closed(region = "AuctionRegion:Circuit", trigger = true)]
+    fn open(&self, env: &Env) {}
+
+    #[when_opened(region = "AuctionRegion:Circuit", trigger = true)]
+    fn close(&self, env: &Env) {}
+}
+
+// Implement the Oracle events.
+impl oracle::Events<Address, MarketData> for AuctionContract {
+    fn on_request(env: &Env, _topic: &Address, envelope: &oracle::Envelope) {
+        require_broker_whitelisted(env, &envelope.broker);
+        envelope.subscriber.require_auth();
+    }
+
+    fn on_sync_receive(env: &Env, topic: &Address, envelope: &oracle::Envelope, data: &MarketData) {
+        require_broker_whitelisted(env, &envelope.broker);
+        store_market_data(env, topic, data);
+    }
+
+    fn on_async_receive(
+        env: &Env,
+        topic: &Address,
+        envelope: &oracle::Envelope,
+        data: &MarketData,
+    ) {
+        require_broker_whitelisted(env, &envelope.broker);
+        // Make sure this cross-contract call is from broker.
+        envelope.broker.require_auth();
+        store_market_data(env, topic, data);
+    }
+}
+
 #[contractimpl]
 impl AuctionContractTrait for AuctionContract {
     fn get_auction(env: Env, auction_id: u64) -> Option<AuctionData> {
-        storage::get_or_else::<DataKey, AuctionData, _, _>(
+        AuctionData::load(&env, auction_id)
+    }
+
+    fn get_min_bid(env: Env, auction_id: u64) -> i128 {
+        let auction_data = AuctionData::load(&env, auction_id).unwrap();
+        dispatcher!(auction_data.settings).calculate_min_bid(
             &env,
-            &DataKey::AuctionData(auction_id),
-            |opt| opt,
+            &auction_data,
+            auction_data.settings.amount,
         )
     }
 
-    fn resolve(env: Env, auction_id: u64) {
-        let auction_data =
-            storage::get::<DataKey, AuctionData>(&env, &DataKey::AuctionData(auction_id)).unwrap();
-        dispatcher!(
-            auction_data.settings.discount_percent > 0
-                && auction_data.settings.discount_frequency > 0
+    fn list_auctions(env: Env, cursor: Option<u64>, limit: u32) -> Vec<AuctionData> {
+        index::page(&env, &AuctionIndex::Active, cursor, limit)
+    }
+
+    fn auctions_by_seller(
+        env: Env,
+        seller: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Vec<AuctionData> {
+        index::page(&env, &AuctionIndex::Seller(seller), cursor, limit)
+    }
+
+    fn auctions_by_token(
+        env: Env,
+        token: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Vec<AuctionData> {
+        index::page(&env, &AuctionIndex::Token(token), cursor, limit)
+    }
+
+    fn auctions_by_market(
+        env: Env,
+        market: Address,
+        cursor: Option<u64>,
+        limit: u32,
+    ) -> Vec<AuctionData> {
+        index::page(&env, &AuctionIndex::Market(market), cursor, limit)
+    }
+
+    fn get_phase(env: Env, auction_id: u64) -> Option<AuctionPhase> {
+        match AuctionData::load(&env, auction_id) {
+            Some(auction_data) => {
+                Some(dispatcher!(auction_data.settings).calculate_phase(&env, &auction_data))
+            }
+            None => StateMachine::<AuctionRegion, AuctionPhase>::new(
+                &AuctionRegion::Dispatcher(auction_id),
+                fsm::StorageType::Persistent,
+            )
+            .get_state(&env),
+        }
+    }
+
+    fn advance(env: Env, auction_id: u64) -> AuctionPhase {
+        let auction_data = AuctionData::load(&env, auction_id).unwrap();
+        dispatcher!(auction_data.settings).advance_phase(&env, auction_id)
+    }
+
+    fn get_outcome(env: Env, auction_id: u64) -> Option<AuctionOutcome> {
+        storage::get_or_else::<DataKey, AuctionOutcome, _, _>(
+            &env,
+            &DataKey::AuctionOutcome(auction_id),
+            |opt| opt,
         )
-        .resolve(&env, auction_id);
+    }
+
+    fn resolve(env: Env, auction_id: u64) -> Option<AuctionOutcome> {
+        extend_instance_ttl(&env);
+
+        // Settled auctions only return their outcome.
+        if let Some(auction_data) = Self::get_auction(env.clone(), auction_id) {
+            let dispatcher = dispatcher!(auction_data.settings);
+
+            // Nothing to resolve before the auction starts.
+            if dispatcher.advance_phase(&env, auction_id) != AuctionPhase::Scheduled {
+                dispatcher.resolve(&env, auction_id);
+            }
+        }
+        Self::get_outcome(env, auction_id)
     }
 
     fn place_bid(
@@ -115,66 +472,130 @@ impl AuctionContractTrait for AuctionContract {
     ) {
         buyer.require_auth();
 
-        let auction_data =
-            storage::get::<DataKey, AuctionData>(&env, &DataKey::AuctionData(auction_id)).unwrap();
+        let auction_data = AuctionData::load(&env, auction_id).unwrap();
 
-        let dispatcher = dispatcher!(
-            auction_data.settings.discount_percent > 0
-                && auction_data.settings.discount_frequency > 0
+        // Bid cancellation and sealed bid reveals remain available while paused,
+        // so that unrevealed deposits are not forfeited.
+        let reveal = salt.is_some()
+            && matches!(
+                auction_data.settings.kind,
+                AuctionKind::SealedBid | AuctionKind::SecondPrice
+            );
+        if amount != 0 && !reveal {
+            AuctionCircuit.require_closed(&env);
+        }
+
+        let quantity = auction_data.settings.amount;
+        dispatcher!(auction_data.settings).place_bid(
+            &env,
+            auction_id,
+            &buyer,
+            &auction_data.settings.market,
+            amount,
+            quantity,
+            &salt,
         );
+    }
 
-        #[cfg(test)]
-        let has_sealed_phase_expired = |_env: &Env, _auction_data: &AuctionData| -> bool { true };
-
-        #[cfg(not(test))]
-        let has_sealed_phase_expired = |env: &Env, auction_data: &AuctionData| -> bool {
-            auction_data.start_time + auction_data.settings.sealed_phase_time
-                <= env.ledger().timestamp()
-        };
-
-        if dispatcher.is_sealed_bid_auction(&auction_data) {
-            let region = AuctionRegion::Dispatcher(auction_id);
-            if has_sealed_phase_expired(&env, &auction_data) {
-                let state_machine = StateMachine::<AuctionRegion, AuctionPhase>::new(
-                    &region,
-                    fsm::StorageType::Instance,
-                );
-                state_machine.set_state(&env, &AuctionPhase::Running);
-            }
+    fn place_bid_with_market(
+        env: Env,
+        auction_id: u64,
+        buyer: Address,
+        market: Address,
+        amount: i128,
+    ) {
+        buyer.require_auth();
+
+        // Bid cancellation remains available while paused.
+        if amount != 0 {
+            AuctionCircuit.require_closed(&env);
         }
 
-        dispatcher.place_bid(&env, auction_id, &buyer, amount, &salt);
+        let auction_data = AuctionData::load(&env, auction_id).unwrap();
+        assert_eq!(auction_data.settings.kind, AuctionKind::Ascending);
+
+        let quantity = auction_data.settings.amount;
+        dispatcher!(auction_data.settings)
+            .place_bid(&env, auction_id, &buyer, &market, amount, quantity, &None);
+    }
+
+    fn place_proxy_bid(env: Env, auction_id: u64, buyer: Address, max_amount: i128) {
+        buyer.require_auth();
+        AuctionCircuit.require_closed(&env);
+
+        let auction_data = AuctionData::load(&env, auction_id).unwrap();
+        assert_eq!(auction_data.settings.kind, AuctionKind::Ascending);
+        assert!(auction_data.settings.markets.is_empty());
+        dispatcher!(auction_data.settings).place_proxy_bid(&env, auction_id, &buyer, max_amount);
+    }
+
+    fn raise_bid(env: Env, auction_id: u64, buyer: Address, amount: i128) {
+        buyer.require_auth();
+        AuctionCircuit.require_closed(&env);
+
+        let auction_data = AuctionData::load(&env, auction_id).unwrap();
+        dispatcher!(auction_data.settings).raise_bid(&env, auction_id, &buyer, amount);
+    }
+
+    fn place_multi_unit_bid(
+        env: Env,
+        auction_id: u64,
+        buyer: Address,
+        quantity: i128,
+        price: i128,
+    ) {
+        buyer.require_auth();
+
+        // Bid cancellation remains available while paused.
+        if quantity != 0 {
+            AuctionCircuit.require_closed(&env);
+            assert!(price > 0);
+        }
+
+        let auction_data = AuctionData::load(&env, auction_id).unwrap();
+        assert_eq!(auction_data.settings.kind, AuctionKind::MultiUnit);
+
+        let amount = quantity.checked_mul(price).unwrap();
+        dispatcher!(auction_data.settings).place_bid(
+            &env,
+            auction_id,
+            &buyer,
+            &auction_data.settings.market,
+            amount,
+            quantity,
+            &None,
+        );
     }
 
     fn place_sealed_bid(env: Env, auction_id: u64, buyer: Address, sealed_amount: BytesN<32>) {
         buyer.require_auth();
+        AuctionCircuit.require_closed(&env);
 
-        let auction_data =
-            storage::get::<DataKey, AuctionData>(&env, &DataKey::AuctionData(auction_id)).unwrap();
-        dispatcher!(
-            auction_data.settings.discount_percent > 0
-                && auction_data.settings.discount_frequency > 0
-        )
-        .place_sealed_bid(&env, auction_id, &buyer, &sealed_amount);
+        let auction_data = AuctionData::load(&env, auction_id).unwrap();
+        dispatcher!(auction_data.settings).place_sealed_bid(
+            &env,
+            auction_id,
+            &buyer,
+            &sealed_amount,
+        );
     }
 
     fn extend(env: Env, auction_id: u64, duration: u64) -> bool {
-        if !storage::get_or_else::<DataKey, AdminData, _, _>(&env, &DataKey::AdminData, |opt| {
-            opt.unwrap()
-        })
-        .extendable_auctions
-        {
+        if !AdminData::load(&env).extendable_auctions {
             false
         } else {
-            let mut auction_data =
-                storage::get::<DataKey, AuctionData>(&env, &DataKey::AuctionData(auction_id))
-                    .unwrap();
+            let mut auction_data = AuctionData::load(&env, auction_id).unwrap();
             auction_data.settings.seller.require_auth();
             auction_data.settings.duration += duration;
-            storage::set::<DataKey, AuctionData>(
-                &env,
-                &DataKey::AuctionData(auction_id),
-                &auction_data,
+            auction_data.store(&env);
+            env.events().publish(
+                (AUCTION, symbol_short!("extended")),
+                AuctionExtended {
+                    version: EVENTS_VERSION,
+                    auction_id,
+                    end_time: auction_data.start_time + auction_data.settings.duration,
+                    extensions: auction_data.extensions,
+                },
             );
             true
         }
@@ -187,24 +608,239 @@ impl AuctionContractTrait for AuctionContract {
         ));
 
         auction_settings.seller.require_auth();
+        AuctionCircuit.require_closed(&env);
+
+        // Scheduled auctions start in the future.
+        let now = env.ledger().timestamp();
+        assert!(auction_settings.start_time == 0 || auction_settings.start_time >= now);
+        let start_time = auction_settings.start_time.max(now);
 
         let mut id = 0u64;
         env.prng().fill(&mut id);
-        let auction_data = AuctionData::new(
-            auction_settings,
-            env.ledger().timestamp(),
-            vec![&env],
-            vec![&env],
-            id,
-        );
-        dispatcher!(
-            auction_data.settings.discount_percent > 0
-                && auction_data.settings.discount_frequency > 0
-        )
-        .start(&env, id, &auction_data);
+        let auction_data =
+            AuctionData::new(auction_settings, start_time, vec![&env], vec![&env], id);
+        dispatcher!(auction_data.settings).start(&env, id, &auction_data);
         id
     }
 
+    fn start_with_hidden_reserve(
+        env: Env,
+        auction_settings: AuctionSettings,
+        reserve_hash: BytesN<32>,
+    ) -> u64 {
+        assert_eq!(auction_settings.kind, AuctionKind::Ascending);
+        assert_eq!(auction_settings.reserve_price, 0);
+
+        let id = Self::start(env.clone(), auction_settings);
+        env.storage()
+            .persistent()
+            .set::<DataKey, BytesN<32>>(&DataKey::HiddenReserve(id), &reserve_hash);
+        AuctionData::load(&env, id).unwrap().extend_ttl(&env);
+        id
+    }
+
+    fn reveal_reserve(env: Env, auction_id: u64, reserve: i128, salt: BytesN<32>) {
+        let auction_data = AuctionData::load(&env, auction_id).unwrap();
+        auction_data.settings.seller.require_auth();
+        dispatcher!(auction_data.settings).reveal_reserve(&env, auction_id, reserve, &salt);
+    }
+
+    fn cancel(env: Env, auction_id: u64) {
+        let auction_data = AuctionData::load(&env, auction_id).unwrap();
+        auction_data.settings.seller.require_auth();
+        dispatcher!(auction_data.settings).cancel(&env, auction_id);
+    }
+
+    fn set_cancellation_policy(env: Env, policy: CancellationPolicy) {
+        Role::ConfigOperator.require_auth(&env);
+        assert!((0..=MAX_BPS).contains(&policy.penalty_rate));
+        env.storage()
+            .instance()
+            .set::<DataKey, CancellationPolicy>(&DataKey::CancellationPolicy, &policy);
+    }
+
+    fn set_forfeiture_rate(env: Env, rate: i128) {
+        Role::ConfigOperator.require_auth(&env);
+        assert!((0..=MAX_BPS).contains(&rate));
+        env.storage()
+            .instance()
+            .set::<DataKey, i128>(&DataKey::ForfeitureRate, &rate);
+    }
+
+    fn set_soft_close_limits(env: Env, limits: SoftClosePolicy) {
+        Role::ConfigOperator.require_auth(&env);
+        env.storage()
+            .instance()
+            .set::<DataKey, SoftClosePolicy>(&DataKey::SoftCloseLimits, &limits);
+    }
+
+    fn pause(env: Env) {
+        Role::Pauser.require_auth(&env);
+        AuctionCircuit.open(&env);
+    }
+
+    fn unpause(env: Env) {
+        Role::Pauser.require_auth(&env);
+        AuctionCircuit.close(&env);
+    }
+
+    fn allow_broker(env: Env, broker: Address) {
+        Role::BrokerManager.require_auth(&env);
+        env.storage()
+            .instance()
+            .set::<DataKey, bool>(&DataKey::BrokerWhitelist(broker), &true);
+    }
+
+    fn deny_broker(env: Env, broker: Address) {
+        Role::BrokerManager.require_auth(&env);
+        env.storage()
+            .instance()
+            .remove::<DataKey>(&DataKey::BrokerWhitelist(broker));
+    }
+
+    fn set_max_price_age(env: Env, max_age: u64) {
+        Role::ConfigOperator.require_auth(&env);
+        assert!(max_age > 0);
+        env.storage()
+            .instance()
+            .set::<DataKey, u64>(&DataKey::MaxPriceAge, &max_age);
+    }
+
+    fn set_commission_rate(env: Env, commission_rate: i128) {
+        let mut admin_data = AdminData::load(&env);
+        Role::ConfigOperator.require_auth(&env);
+        assert!((0..=MAX_BPS).contains(&commission_rate));
+        env.events().publish(
+            (ADMIN, symbol_short!("rate")),
+            CommissionRateUpdated {
+                version: EVENTS_VERSION,
+                old_rate: admin_data.commission_rate,
+                new_rate: commission_rate,
+            },
+        );
+        admin_data.commission_rate = commission_rate;
+        admin_data.store(&env);
+    }
+
+    fn set_anti_snipe_time(env: Env, anti_snipe_time: u64) {
+        let mut admin_data = AdminData::load(&env);
+        Role::ConfigOperator.require_auth(&env);
+        assert!(anti_snipe_time <= MAX_ANTI_SNIPE_TIME);
+        env.events().publish(
+            (ADMIN, symbol_short!("antisnipe")),
+            AntiSnipeTimeUpdated {
+                version: EVENTS_VERSION,
+                old_time: admin_data.anti_snipe_time,
+                new_time: anti_snipe_time,
+            },
+        );
+        admin_data.anti_snipe_time = anti_snipe_time;
+        admin_data.store(&env);
+    }
+
+    fn set_extendable_auctions(env: Env, extendable_auctions: bool) {
+        let mut admin_data = AdminData::load(&env);
+        Role::ConfigOperator.require_auth(&env);
+        env.events().publish(
+            (ADMIN, symbol_short!("extend")),
+            ExtendableAuctionsUpdated {
+                version: EVENTS_VERSION,
+                extendable_auctions,
+            },
+        );
+        admin_data.extendable_auctions = extendable_auctions;
+        admin_data.store(&env);
+    }
+
+    fn propose_admin(env: Env, new_admin: Address) {
+        let admin_data = AdminData::load(&env);
+        admin_data.admin.require_auth();
+        env.storage()
+            .instance()
+            .set::<DataKey, Address>(&DataKey::PendingAdmin, &new_admin);
+        env.events().publish(
+            (ADMIN, symbol_short!("proposed")),
+            AdminProposed {
+                version: EVENTS_VERSION,
+                admin: admin_data.admin,
+                proposed_admin: new_admin,
+            },
+        );
+    }
+
+    fn accept_admin(env: Env) {
+        let new_admin = env
+            .storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::PendingAdmin)
+            .expect("No pending admin");
+        new_admin.require_auth();
+        env.storage()
+            .instance()
+            .remove::<DataKey>(&DataKey::PendingAdmin);
+
+        let mut admin_data = AdminData::load(&env);
+        env.events().publish(
+            (ADMIN, symbol_short!("transfer")),
+            AdminTransferred {
+                version: EVENTS_VERSION,
+                old_admin: admin_data.admin,
+                new_admin: new_admin.clone(),
+            },
+        );
+        admin_data.admin = new_admin;
+        admin_data.store(&env);
+    }
+
+    fn grant_role(env: Env, role: Role, account: Address) {
+        AdminData::load(&env).admin.require_auth();
+        env.storage()
+            .instance()
+            .set::<DataKey, Address>(&DataKey::Role(role), &account);
+        env.events().publish(
+            (ADMIN, symbol_short!("granted")),
+            RoleUpdated {
+                version: EVENTS_VERSION,
+                role,
+                account,
+            },
+        );
+    }
+
+    fn revoke_role(env: Env, role: Role) {
+        AdminData::load(&env).admin.require_auth();
+        let account = role.holder(&env);
+        env.storage()
+            .instance()
+            .remove::<DataKey>(&DataKey::Role(role));
+        env.events().publish(
+            (ADMIN, symbol_short!("revoked")),
+            RoleUpdated {
+                version: EVENTS_VERSION,
+                role,
+                account,
+            },
+        );
+    }
+
+    fn get_role(env: Env, role: Role) -> Address {
+        role.holder(&env)
+    }
+
+    fn set_royalty_contract(env: Env, royalty_contract: Option<Address>) {
+        Role::ConfigOperator.require_auth(&env);
+        match royalty_contract {
+            Some(address) => env
+                .storage()
+                .instance()
+                .set::<DataKey, Address>(&DataKey::RoyaltyContract, &address),
+            None => env
+                .storage()
+                .instance()
+                .remove::<DataKey>(&DataKey::RoyaltyContract),
+        }
+    }
+
     fn initialize(
         env: Env,
         admin: Address,
@@ -217,30 +853,68 @@ impl AuctionContractTrait for AuctionContract {
             &DataKey::AdminData
         ));
 
-        storage::set::<DataKey, AdminData>(
-            &env,
-            &DataKey::AdminData,
-            &AdminData {
-                admin,
-                anti_snipe_time: anti_snipe_time.min(60),
-                commission_rate: commission_rate.max(0).min(100),
-                extendable_auctions,
-            },
-        );
+        AdminData {
+            admin,
+            anti_snipe_time: anti_snipe_time.min(MAX_ANTI_SNIPE_TIME),
+            commission_rate: commission_rate.clamp(0, MAX_BPS),
+            extendable_auctions,
+        }
+        .store(&env);
     }
 
     fn upgrade(env: Env, wasm_hash: BytesN<32>) {
-        storage::get::<DataKey, AdminData>(&env, &DataKey::AdminData)
-            .unwrap()
-            .admin
-            .require_auth();
+        Role::Upgrader.require_auth(&env);
         env.deployer().update_current_contract_wasm(wasm_hash);
     }
 
+    fn migrate(env: Env, auction_ids: Vec<u64>) {
+        Role::Upgrader.require_auth(&env);
+        for auction_id in auction_ids.iter() {
+            if AuctionData::load(&env, auction_id).is_none() {
+                let region = AuctionRegion::Dispatcher(auction_id);
+                migrate_entry(&env, &region);
+                if env.storage().persistent().has(&region) {
+                    env.storage()
+                        .persistent()
+                        .extend_ttl(&region, OUTCOME_TTL, OUTCOME_TTL);
+                }
+            }
+        }
+    }
+
     fn version(env: Env) -> Vec<u32> {
-        vec![&env, 0, 1, 4] // "0.1.4"
+        vec![&env, 0, 2, 0] // "0.2.0"
     }
 }
 
+#[cfg(test)]
+#[contractimpl]
+impl AuctionContract {
+    pub fn test_oracle_feed(env: Env, topic: Address, price: i128, asset: Address) {
+        storage::set::<MarketDataKey, MarketData>(
+            &env,
+            &MarketDataKey::Index(topic),
+            &MarketData {
+                price,
+                asset,
+                timestamp: env.ledger().timestamp(),
+            },
+        );
+    }
+}
+
+// Price feeds cannot be dated in the future.
+fn store_market_data(env: &Env, topic: &Address, data: &MarketData) {
+    assert!(data.timestamp <= env.ledger().timestamp());
+    storage::set::<MarketDataKey, MarketData>(env, &MarketDataKey::Index(topic.clone()), data);
+}
+
+fn require_broker_whitelisted(env: &Env, broker: &Address) -> bool {
+    env.storage()
+        .instance()
+        .get::<DataKey, bool>(&DataKey::BrokerWhitelist(broker.clone()))
+        .unwrap()
+}
+
 #[cfg(test)]
 mod test;
@@ -0,0 +1,151 @@
+/*
+    Date: 2023
+    Author: Fred Kyung-jin Rezeau <fred@litemint.com>
+    Copyright (c) 2023 Litemint LLC
+
+    MIT License
+*/
+
+//! Client for the Litemint royalty contract (see: litemint-royalty-contract).
+//! Types mirror the royalty contract interface.
+
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contractclient, contracttype, symbol_short, token, vec, Address, Env, IntoVal, Symbol, Vec,
+};
+
+use crate::events::{RoyaltySkipped, AUCTION, EVENTS_VERSION};
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Compensation {
+    Fixed,
+    Percentage,
+    Subscription,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Recipient {
+    pub address: Address,
+    pub share: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Terms {
+    pub licensor: Address,
+    pub property: Address,
+    pub lien: Address,
+    pub compensation: Compensation,
+    pub royalty_interest: i128,
+    pub transfer_fee: i128,
+    pub currency: Address,
+    pub recur_period: u64,
+    pub grace_period: u64,
+    pub recipients: Vec<Recipient>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LicenseStatus {
+    Unpaid,
+    Paid,
+    Breached,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct License {
+    pub terms: Terms,
+    pub licensee: Address,
+    pub created_time: u64,
+    pub recur_time: u64,
+    pub grace_time: u64,
+    pub status: LicenseStatus,
+    pub transferring: bool,
+}
+
+#[allow(dead_code)]
+#[contractclient(name = "RoyaltyClient")]
+pub trait RoyaltyInterface {
+    fn pay_for(env: Env, property: Address, licensee: Address, payer: Address) -> License;
+    fn quote(env: Env, property: Address, licensee: Address) -> Vec<(i128, Address)>;
+}
+
+// Pay the royalty due by `buyer` for `property` out of `amount` escrowed in `market`.
+// Return the amount paid, zero when no royalty is due.
+// Royalties that can never be settled from the bid (different currency, or exceeding `amount`)
+// are skipped with a (AUCTION, "skipped") event, leaving the license unpaid by the buyer.
+// Panics when the royalty contract fails (e.g., paused), so that the winning bid remains
+// in escrow and settlement is retried.
+pub fn pay_royalty(
+    env: &Env,
+    auction_id: u64,
+    royalty_contract: &Address,
+    property: &Address,
+    market: &Address,
+    buyer: &Address,
+    amount: i128,
+) -> i128 {
+    let royalty = RoyaltyClient::new(env, royalty_contract);
+    let interest = royalty.quote(property, buyer);
+
+    // Authorize the royalty contract to collect each payment from escrow.
+    let mut total = 0;
+    let mut auth_entries = vec![env];
+    for (value, currency) in interest.iter() {
+        if value <= 0 {
+            continue;
+        }
+        if currency != *market {
+            return skip_royalty(env, auction_id, property, buyer, interest);
+        }
+        total += value;
+        auth_entries.push_back(InvokerContractAuthEntry::Contract(SubContractInvocation {
+            context: ContractContext {
+                contract: market.clone(),
+                fn_name: Symbol::new(env, "transfer"),
+                args: (
+                    env.current_contract_address(),
+                    royalty_contract.clone(),
+                    value,
+                )
+                    .into_val(env),
+            },
+            sub_invocations: vec![env],
+        }));
+    }
+    if total == 0 {
+        return 0;
+    }
+    if total > amount {
+        return skip_royalty(env, auction_id, property, buyer, interest);
+    }
+
+    let market = token::Client::new(env, market);
+    let balance = market.balance(&env.current_contract_address());
+    env.authorize_as_current_contract(auth_entries);
+    royalty.pay_for(property, buyer, &env.current_contract_address());
+    balance - market.balance(&env.current_contract_address())
+}
+
+fn skip_royalty(
+    env: &Env,
+    auction_id: u64,
+    property: &Address,
+    buyer: &Address,
+    interest: Vec<(i128, Address)>,
+) -> i128 {
+    env.events().publish(
+        (AUCTION, symbol_short!("skipped")),
+        RoyaltySkipped {
+            version: EVENTS_VERSION,
+            auction_id,
+            property: property.clone(),
+            buyer: buyer.clone(),
+            interest,
+        },
+    );
+    0
+}
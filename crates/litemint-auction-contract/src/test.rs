@@ -6,13 +6,18 @@
     MIT License
 */
 
-use crate::{types::{AuctionData, AuctionSettings}, AuctionContract, AuctionContractClient};
+use crate::{events::{AdminTransferred, RoleUpdated, AuctionExtended, CommissionRateUpdated, AuctionSettled, AuctionStarted, BidPlaced, Payment, RoyaltySkipped, Settlement, EVENTS_VERSION}, types::{AdminData, AuctionData, AuctionIndex, AuctionKind, AuctionPhase, AuctionRegion, AuctionSettings, BidData, CancellationPolicy, DataKey, LegacyAuctionData, LegacyAuctionSettings, LegacyBidData, OutcomeKind, Role, SoftClosePolicy}, AuctionContract, AuctionContractClient};
 extern crate std;
 
+use litemint_royalty_contract::{
+    types::{Compensation, LicenseStatus, Terms},
+    RoyaltyContract, RoyaltyContractClient,
+};
+
 use core::panic::AssertUnwindSafe;
 use soroban_sdk::{
-    testutils::{Address as _, Logs},
-    token, vec, Address, Env, Bytes, BytesN,
+    testutils::{Address as _, Events, Ledger, Logs},
+    token, vec, xdr::ToXdr, Address, Env, Bytes, BytesN, Symbol, TryFromVal,
 };
 use std::panic::catch_unwind;
 use std::println;
@@ -26,7 +31,7 @@ fn create_token_contract<'a>(e: &Env, admin: &Address) -> (TokenClient<'a>, Toke
         TokenAdminClient::new(e, &contract_address),
     )
 }
-fn create_auction_contract(e: &Env) -> AuctionContractClient {
+fn create_auction_contract(e: &Env) -> AuctionContractClient<'_> {
     AuctionContractClient::new(e, &e.register_contract(None, AuctionContract {}))
 }
 
@@ -39,12 +44,13 @@ fn start_auction(
 }
 
 #[test]
+#[allow(clippy::assertions_on_constants, clippy::needless_borrow, clippy::single_match)]
 fn test_ascending_descending_auctions() {
     let env = Env::default();
     env.mock_all_auths();
 
     let initial_balance = 1000;
-    let commission_rate = 10;
+    let commission_rate = 1000;
     let token_supply: i128 = 5;
     let extendable_auctions = true;
     let token_admin = Address::generate(&env);
@@ -61,7 +67,7 @@ fn test_ascending_descending_auctions() {
     }
 
     // Initialize the contract. Sets the admin, anti_snipe_time (in seconds)
-    // and commission_rate (in percent).
+    // and commission_rate (in basis points).
     auction_contract.initialize(&token_admin, &300, &commission_rate, &extendable_auctions);
 
     // Configure a descending price auction (Dutch auction).
@@ -78,6 +84,14 @@ fn test_ascending_descending_auctions() {
         compounded_discount: false,
         sealed_phase_time: 0,
         sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Descending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
     };
 
     // Start the auction.
@@ -194,11 +208,10 @@ fn test_ascending_descending_auctions() {
         None => {}
     }
 
-    // Try to resolve the auction (should have no effect).
-    result = catch_unwind(AssertUnwindSafe(|| {
-        auction_contract.resolve(&auction_id);
-    }));
-    assert!(result.is_err(), "No auction to resolve.");
+    // Try to resolve the auction (should have no effect and return the outcome).
+    let outcome = auction_contract.resolve(&auction_id).unwrap();
+    assert_eq!(outcome.outcome, OutcomeKind::Won);
+    assert_eq!(outcome.winners.get_unchecked(0).buyer, bidders[1]);
 
     // Verify all balances to check the auction executed properly.
     assert_eq!(
@@ -211,14 +224,21 @@ fn test_ascending_descending_auctions() {
     assert_eq!(token.balance(&auction_contract.address), 0);
     assert_eq!(
         market.balance(&token_admin),
-        auction_settings.ask_price * commission_rate / 100
+        auction_settings.ask_price * commission_rate / 10_000
     );
     assert_eq!(
         market.balance(&seller),
-        auction_settings.ask_price * (100 - commission_rate) / 100
+        auction_settings.ask_price * (10_000 - commission_rate) / 10_000
     );
     assert_eq!(token.balance(&seller), token_supply - 1);
 
+    // Discount is not supported for ascending price auctions.
+    auction_settings.kind = AuctionKind::Ascending;
+    result = catch_unwind(AssertUnwindSafe(|| {
+        start_auction(&env, &auction_contract, &auction_settings);
+    }));
+    assert!(result.is_err(), "Should panic. Unsupported discount.");
+
     // Start an ascending price auction.
     auction_settings.discount_percent = 0;
     auction_settings.discount_frequency = 0;
@@ -236,11 +256,10 @@ fn test_ascending_descending_auctions() {
         None => {}
     }
 
-    // Try to resolve the auction (should have no effect).
-    result = catch_unwind(AssertUnwindSafe(|| {
-        auction_contract.resolve(&auction_id);
-    }));
-    assert!(result.is_err(), "No auction to resolve.");
+    // Try to resolve the auction (should have no effect and return the outcome).
+    let outcome = auction_contract.resolve(&auction_id).unwrap();
+    assert_eq!(outcome.kind, AuctionKind::Ascending);
+    assert_eq!(outcome.clearing_price, auction_settings.ask_price);
 
     // Verify the balances.
     assert_eq!(
@@ -252,11 +271,11 @@ fn test_ascending_descending_auctions() {
     assert_eq!(token.balance(&auction_contract.address), 0);
     assert_eq!(
         market.balance(&token_admin),
-        (auction_settings.ask_price * commission_rate / 100) * 2
+        (auction_settings.ask_price * commission_rate / 10_000) * 2
     );
     assert_eq!(
         market.balance(&seller),
-        (auction_settings.ask_price * (100 - commission_rate) / 100) * 2
+        (auction_settings.ask_price * (10_000 - commission_rate) / 10_000) * 2
     );
     assert_eq!(token.balance(&seller), token_supply - 2);
 
@@ -285,13 +304,14 @@ fn test_ascending_descending_auctions() {
 }
 
 #[test]
+#[allow(clippy::needless_borrow, clippy::redundant_field_names)]
 fn test_sealed_bid_auctions() {
     let env = Env::default();
     env.mock_all_auths();
 
     let duration = 50;
     let initial_balance = 1000;
-    let commission_rate = 10;
+    let commission_rate = 1000;
     let extendable_auctions = true;
     let token_supply: i128 = 5;
     let token_admin = Address::generate(&env);
@@ -308,7 +328,7 @@ fn test_sealed_bid_auctions() {
     }
 
     // Initialize the contract. Sets the admin, anti_snipe_time (in seconds)
-    // and commission_rate (in percent).
+    // and commission_rate (in basis points).
     auction_contract.initialize(
         &token_admin,
         &duration,
@@ -330,10 +350,20 @@ fn test_sealed_bid_auctions() {
         compounded_discount: false,
         sealed_phase_time : 1,
         sealed_bid_deposit: 10,
+        reveal_phase_time: 10,
+        kind: AuctionKind::SealedBid,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
     };
 
     // Start the auction.
+    env.ledger().with_mut(|li| li.timestamp = 0);
     let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    assert_eq!(auction_contract.get_phase(&auction_id), Some(AuctionPhase::Committing));
 
     // Placing a regular bid or trying to reveal a bid should panic
     let result = catch_unwind(AssertUnwindSafe(|| {
@@ -341,13 +371,14 @@ fn test_sealed_bid_auctions() {
     }));
     assert!(result.is_err(), "Should panic. Invalid phase + no bids to reveal");
 
-    // Bid is sealed with sha256([big_endian_amount;16][salt;32][big_endian_auction_id;8]).
+    // Bid is sealed with sha256([big_endian_amount;16][salt;32][big_endian_auction_id;8][buyer_address_xdr]).
     let mut test_auction = auction_contract.get_auction(&auction_id).unwrap();
 
     let salt = BytesN::from_array(&env, &[0_u8; 32]);
     let mut sealed_data = Bytes::from_array(&env, &auction_settings.reserve_price.to_be_bytes());
     sealed_data.append(&Bytes::from_slice(&env, &salt.to_array())); // Salt.
     sealed_data.append(&Bytes::from_slice(&env, &test_auction.id.to_be_bytes()));
+    sealed_data.append(&bidders[0].clone().to_xdr(&env)); // Buyer.
     let hash = env.crypto().sha256(&sealed_data);
 
     // Submit a sealed bid.    
@@ -363,7 +394,13 @@ fn test_sealed_bid_auctions() {
     assert_eq!(market.balance(&bidders[0]), initial_balance - auction_settings.sealed_bid_deposit);
     assert_eq!(market.balance(&auction_contract.address), auction_settings.sealed_bid_deposit);
 
-    // Reveal the bid
+    // Reveal the bid once the commit phase is over.
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[0], &(auction_settings.reserve_price), &Some(salt.clone()));
+    }));
+    assert!(result.is_err(), "Should panic. Invalid phase.");
+    env.ledger().with_mut(|li| li.timestamp = 1);
+    assert_eq!(auction_contract.get_phase(&auction_id), Some(AuctionPhase::Revealing));
     auction_contract.place_bid(&auction_id, &bidders[0], &(auction_settings.reserve_price), &Some(salt));
 
     // The revealed bid amount should now be recorded.
@@ -381,13 +418,14 @@ fn test_sealed_bid_auctions() {
 }
 
 #[test]
+#[allow(clippy::bool_assert_comparison, clippy::needless_borrow, clippy::redundant_field_names, clippy::single_match)]
 fn test_anti_sniping() {
     let env = Env::default();
     env.mock_all_auths();
 
     let duration = 50;
     let initial_balance = 1000;
-    let commission_rate = 10;
+    let commission_rate = 1000;
     let extendable_auctions = true;
     let token_supply: i128 = 5;
     let token_admin = Address::generate(&env);
@@ -404,7 +442,7 @@ fn test_anti_sniping() {
     }
 
     // Initialize the contract. Sets the admin, anti_snipe_time (in seconds)
-    // and commission_rate (in percent).
+    // and commission_rate (in basis points).
     auction_contract.initialize(
         &token_admin,
         &duration,
@@ -426,6 +464,14 @@ fn test_anti_sniping() {
         compounded_discount: false,
         sealed_phase_time : 0,
         sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Descending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
     };
 
     // Start the auction.
@@ -451,3 +497,2397 @@ fn test_anti_sniping() {
         None => {}
     }
 }
+
+#[test]
+fn test_pausable_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let extendable_auctions = true;
+    let token_supply: i128 = 5;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &token_supply);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &60, &commission_rate, &extendable_auctions);
+
+    // Configure an ascending price auction (English auction).
+    let auction_settings: AuctionSettings = AuctionSettings {
+        seller,
+        token: token.address.clone(),
+        amount: 1,
+        duration: 180,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 900,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // Start the auction and place a bid.
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_bid(&auction_id, &bidders[0], &(auction_settings.reserve_price), &None);
+
+    // Pause the contract.
+    auction_contract.pause();
+
+    // Starting an auction should panic while paused.
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        start_auction(&env, &auction_contract, &auction_settings);
+    }));
+    assert!(result.is_err(), "Should panic. Contract is paused.");
+
+    // Placing a bid should panic while paused.
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[1], &(auction_settings.reserve_price), &None);
+    }));
+    assert!(result.is_err(), "Should panic. Contract is paused.");
+
+    // Placing a sealed bid should panic while paused.
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_sealed_bid(&auction_id, &bidders[1], &BytesN::from_array(&env, &[0_u8; 32]));
+    }));
+    assert!(result.is_err(), "Should panic. Contract is paused.");
+
+    // Resolving and cancelling bids remain available.
+    auction_contract.resolve(&auction_id);
+    auction_contract.place_bid(&auction_id, &bidders[0], &0, &None);
+    assert_eq!(market.balance(&bidders[0]), initial_balance);
+    assert_eq!(auction_contract.get_auction(&auction_id).unwrap().bids.len(), 0);
+
+    // Unpause the contract, bidding is restored.
+    auction_contract.unpause();
+    auction_contract.place_bid(&auction_id, &bidders[1], &(auction_settings.reserve_price), &None);
+    assert_eq!(auction_contract.get_auction(&auction_id).unwrap().bids.len(), 1);
+}
+
+#[test]
+fn test_second_price_auctions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let duration = 50;
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let extendable_auctions = true;
+    let token_supply: i128 = 5;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &token_supply);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &extendable_auctions);
+
+    // Configure a second price sealed bid auction (Vickrey auction).
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 10,
+        discount_frequency: 20,
+        compounded_discount: false,
+        sealed_phase_time: 1,
+        sealed_bid_deposit: 10,
+        reveal_phase_time: 10,
+        kind: AuctionKind::SecondPrice,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // Discount is not supported for second price auctions.
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        start_auction(&env, &auction_contract, &auction_settings);
+    }));
+    assert!(result.is_err(), "Should panic. Unsupported discount.");
+
+    // Second price auctions require sealed bids.
+    auction_settings.discount_percent = 0;
+    auction_settings.discount_frequency = 0;
+    auction_settings.sealed_phase_time = 0;
+    auction_settings.sealed_bid_deposit = 0;
+    result = catch_unwind(AssertUnwindSafe(|| {
+        start_auction(&env, &auction_contract, &auction_settings);
+    }));
+    assert!(result.is_err(), "Should panic. Not a sealed bid auction.");
+
+    // Start the auction.
+    auction_settings.sealed_phase_time = 1;
+    auction_settings.sealed_bid_deposit = 10;
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    assert_eq!(auction_contract.get_auction(&auction_id).unwrap().settings.kind, AuctionKind::SecondPrice);
+
+    // Submit and reveal the sealed bids.
+    let amounts = [300_i128, 200_i128];
+    let salt = BytesN::from_array(&env, &[0_u8; 32]);
+    for (bidder, amount) in bidders.iter().zip(amounts.iter()) {
+        let mut sealed_data = Bytes::from_array(&env, &amount.to_be_bytes());
+        sealed_data.append(&Bytes::from_slice(&env, &salt.to_array()));
+        sealed_data.append(&Bytes::from_slice(&env, &auction_id.to_be_bytes()));
+        sealed_data.append(&bidder.clone().to_xdr(&env));
+        auction_contract.place_sealed_bid(&auction_id, bidder, &env.crypto().sha256(&sealed_data));
+    }
+    env.ledger().with_mut(|li| li.timestamp = 1);
+    for (bidder, amount) in bidders.iter().zip(amounts.iter()) {
+        auction_contract.place_bid(&auction_id, bidder, amount, &Some(salt.clone()));
+    }
+
+    // Auction should not resolve before the end time.
+    auction_contract.resolve(&auction_id);
+    assert_eq!(auction_contract.get_auction(&auction_id).unwrap().bids.len(), 2);
+
+    // Resolve the auction after the end time.
+    env.ledger().with_mut(|li| li.timestamp = duration + 1);
+    auction_contract.resolve(&auction_id);
+    assert!(auction_contract.get_auction(&auction_id).is_none());
+
+    // The highest bidder wins and pays the second highest bid.
+    let price = amounts[1];
+    assert_eq!(token.balance(&bidders[0]), auction_settings.amount);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - price);
+    assert_eq!(market.balance(&bidders[1]), initial_balance);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+    assert_eq!(market.balance(&token_admin), price * commission_rate / 10_000);
+    assert_eq!(market.balance(&seller), price * (10_000 - commission_rate) / 10_000);
+
+    // Tied top bids go to the earliest bid, paying the tied amount.
+    let start_time = duration + 1;
+    env.ledger().with_mut(|li| li.timestamp = start_time);
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    let amount = 150_i128;
+    for bidder in bidders.iter() {
+        let mut sealed_data = Bytes::from_array(&env, &amount.to_be_bytes());
+        sealed_data.append(&Bytes::from_slice(&env, &salt.to_array()));
+        sealed_data.append(&Bytes::from_slice(&env, &auction_id.to_be_bytes()));
+        sealed_data.append(&bidder.clone().to_xdr(&env));
+        auction_contract.place_sealed_bid(&auction_id, bidder, &env.crypto().sha256(&sealed_data));
+    }
+    env.ledger().with_mut(|li| li.timestamp = start_time + 1);
+    auction_contract.place_bid(&auction_id, &bidders[1], &amount, &Some(salt.clone()));
+    auction_contract.place_bid(&auction_id, &bidders[0], &amount, &Some(salt.clone()));
+    env.ledger().with_mut(|li| li.timestamp = start_time + duration + 1);
+    let outcome = auction_contract.resolve(&auction_id).unwrap();
+    assert_eq!(outcome.winners.len(), 1);
+    assert_eq!(outcome.winners.get_unchecked(0).buyer, bidders[1]);
+    assert_eq!(outcome.winners.get_unchecked(0).amount, amount);
+    assert_eq!(token.balance(&bidders[1]), auction_settings.amount);
+    assert_eq!(market.balance(&bidders[1]), initial_balance - amount);
+}
+
+#[test]
+fn test_multi_unit_auctions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let duration = 50;
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let extendable_auctions = true;
+    let token_supply: i128 = 10;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &token_supply);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &extendable_auctions);
+
+    // Configure a uniform price auction for a fungible lot.
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: token_supply,
+        duration,
+        market: market.address.clone(),
+        reserve_price: 5,
+        ask_price: 100,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::MultiUnit,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // Ask price (buy now) is not supported for multi-unit auctions.
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        start_auction(&env, &auction_contract, &auction_settings);
+    }));
+    assert!(result.is_err(), "Should panic. Unsupported ask price.");
+
+    auction_settings.ask_price = 0;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    assert_eq!(token.balance(&auction_contract.address), token_supply);
+
+    // Quantity cannot exceed the lot and unit price cannot be below the reserve price.
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_multi_unit_bid(&auction_id, &bidders[3], &(token_supply + 1), &10);
+    }));
+    assert!(result.is_err(), "Should panic. Quantity exceeds the lot.");
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_multi_unit_bid(&auction_id, &bidders[3], &2, &4);
+    }));
+    assert!(result.is_err(), "Should panic. Unit price below reserve.");
+
+    // Place and cancel a bid.
+    auction_contract.place_multi_unit_bid(&auction_id, &bidders[3], &2, &8);
+    assert_eq!(market.balance(&bidders[3]), initial_balance - 16);
+    auction_contract.place_multi_unit_bid(&auction_id, &bidders[3], &0, &0);
+    assert_eq!(market.balance(&bidders[3]), initial_balance);
+
+    // Bids are escrowed in full: 6 units @ 20, 3 units @ 15, 4 units @ 10.
+    let bids = [(6_i128, 20_i128), (3_i128, 15_i128), (4_i128, 10_i128)];
+    for (bidder, (quantity, price)) in bidders.iter().zip(bids.iter()) {
+        auction_contract.place_multi_unit_bid(&auction_id, bidder, quantity, price);
+        assert_eq!(market.balance(bidder), initial_balance - quantity * price);
+    }
+
+    // Auction should not resolve before the end time.
+    auction_contract.resolve(&auction_id);
+    assert_eq!(auction_contract.get_auction(&auction_id).unwrap().bids.len(), 3);
+
+    // Resolve the auction after the end time.
+    env.ledger().with_mut(|li| li.timestamp = duration + 1);
+    auction_contract.resolve(&auction_id);
+    assert!(auction_contract.get_auction(&auction_id).is_none());
+
+    // Highest unit prices are filled first, the last bid is partially filled.
+    // All winners pay the clearing price (lowest accepted unit price).
+    let clearing_price = 10;
+    let filled = [6_i128, 3_i128, 1_i128];
+    for (bidder, quantity) in bidders.iter().zip(filled.iter()) {
+        assert_eq!(token.balance(bidder), *quantity);
+        assert_eq!(market.balance(bidder), initial_balance - quantity * clearing_price);
+    }
+    assert_eq!(token.balance(&auction_contract.address), 0);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+    assert_eq!(market.balance(&token_admin), token_supply * clearing_price * commission_rate / 10_000);
+    assert_eq!(market.balance(&seller), token_supply * clearing_price * (10_000 - commission_rate) / 10_000);
+}
+
+#[test]
+fn test_royalty_payout() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let royalty_interest = 50;
+    let transfer_fee = 10;
+    let token_admin = Address::generate(&env);
+    let licensor = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (lien, lien_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let royalty_contract = RoyaltyContractClient::new(&env, &env.register_contract(None, RoyaltyContract {}));
+
+    // Initialize the balances.
+    token_admin_client.mint(&licensor, &1);
+    lien_admin_client.mint(&licensor, &1);
+    market_admin_client.mint(&seller, &initial_balance);
+    market_admin_client.mint(&buyer, &initial_balance);
+
+    // Register the property with the royalty contract (no commission).
+    royalty_contract.initialize(&token_admin, &0);
+    royalty_contract.add_property(&Terms {
+        licensor: licensor.clone(),
+        property: token.address.clone(),
+        lien: lien.address.clone(),
+        compensation: Compensation::Fixed,
+        royalty_interest,
+        transfer_fee,
+        currency: market.address.clone(),
+        recur_period: 0,
+        grace_period: 60,
+        recipients: vec![&env],
+    });
+
+    // Seller acquires the property and pays the royalty.
+    token.transfer(&licensor, &seller, &1);
+    royalty_contract.pay(&token.address, &seller);
+    assert_eq!(market.balance(&licensor), royalty_interest + transfer_fee);
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &false);
+    auction_contract.set_royalty_contract(&Some(royalty_contract.address.clone()));
+
+    // Configure an ascending price auction with "buy now" option.
+    let auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 180,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 500,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+    assert_eq!(royalty_contract.quote(&token.address, &buyer).len(), 2);
+
+    // Buyer wins the auction instantly.
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_bid(&auction_id, &buyer, &auction_settings.ask_price, &None);
+    assert!(auction_contract.get_auction(&auction_id).is_none());
+
+    // The royalty is routed out of the seller share.
+    let price = auction_settings.ask_price;
+    let royalty = royalty_interest + transfer_fee;
+    assert_eq!(token.balance(&buyer), 1);
+    assert_eq!(market.balance(&buyer), initial_balance - price);
+    assert_eq!(market.balance(&token_admin), price * commission_rate / 10_000);
+    assert_eq!(market.balance(&seller), initial_balance - royalty + price * (10_000 - commission_rate) / 10_000 - royalty);
+    assert_eq!(market.balance(&licensor), royalty * 2);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+
+    // The license is marked as paid by the buyer.
+    let license = royalty_contract.execute(&token.address);
+    assert_eq!(license.licensee, buyer);
+    assert_eq!(license.status, LicenseStatus::Paid);
+    assert_eq!(royalty_contract.quote(&token.address, &buyer).len(), 0);
+
+    // Without royalty contract, the seller receives the full share.
+    auction_contract.set_royalty_contract(&None);
+    market_admin_client.mint(&seller, &initial_balance);
+    let auction_id = start_auction(&env, &auction_contract, &AuctionSettings { seller: buyer.clone(), ..auction_settings.clone() });
+    auction_contract.place_bid(&auction_id, &seller, &auction_settings.ask_price, &None);
+    assert_eq!(token.balance(&seller), 1);
+    assert_eq!(market.balance(&buyer), initial_balance - price + price * (10_000 - commission_rate) / 10_000);
+    assert_eq!(royalty_contract.get_license(&token.address).unwrap().licensee, buyer);
+
+    // Royalties that cannot be settled (royalty contract paused) keep the winning bid in escrow.
+    auction_contract.set_royalty_contract(&Some(royalty_contract.address.clone()));
+    let collector = Address::generate(&env);
+    market_admin_client.mint(&collector, &initial_balance);
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_bid(&auction_id, &collector, &auction_settings.reserve_price, &None);
+    royalty_contract.pause();
+    env.ledger().with_mut(|li| li.timestamp = auction_settings.duration + 1);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.resolve(&auction_id);
+    }));
+    assert!(result.is_err(), "Should panic. Royalty cannot be settled.");
+    assert!(auction_contract.get_auction(&auction_id).is_some());
+    assert_eq!(market.balance(&auction_contract.address), auction_settings.reserve_price);
+
+    // Settlement succeeds once the royalty can be paid.
+    royalty_contract.unpause();
+    auction_contract.resolve(&auction_id);
+    assert_eq!(token.balance(&collector), 1);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+    assert_eq!(royalty_contract.execute(&token.address).licensee, collector);
+
+    // Royalties exceeding the seller share are skipped, the buyer remains liable for the license.
+    let fan = Address::generate(&env);
+    market_admin_client.mint(&fan, &initial_balance);
+    let low_price = 50;
+    let auction_id = start_auction(&env, &auction_contract, &AuctionSettings { seller: collector.clone(), reserve_price: low_price, ..auction_settings.clone() });
+    auction_contract.place_bid(&auction_id, &fan, &low_price, &None);
+    env.ledger().with_mut(|li| li.timestamp += auction_settings.duration + 1);
+    auction_contract.resolve(&auction_id);
+    let skipped = env.events().all().iter().find_map(|(_, topics, data)| {
+        match Symbol::try_from_val(&env, &topics.get_unchecked(1)) {
+            Ok(symbol) if symbol == Symbol::new(&env, "skipped") => Some(RoyaltySkipped::try_from_val(&env, &data).unwrap()),
+            _ => None,
+        }
+    }).unwrap();
+    assert_eq!(skipped.auction_id, auction_id);
+    assert_eq!(skipped.buyer, fan);
+    assert_eq!(skipped.interest, royalty_contract.quote(&token.address, &fan));
+    assert_eq!(token.balance(&fan), 1);
+    assert_eq!(market.balance(&collector), initial_balance - auction_settings.reserve_price + low_price * (10_000 - commission_rate) / 10_000);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+    assert_eq!(royalty_contract.execute(&token.address).status, LicenseStatus::Unpaid);
+}
+
+#[test]
+fn test_basis_point_rates() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 10000;
+    let commission_rate = 250; // 2.5%
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let buyer = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+
+    token_admin_client.mint(&seller, &10);
+    market_admin_client.mint(&buyer, &initial_balance);
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &false);
+
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 180,
+        market: market.address.clone(),
+        reserve_price: 1,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // Commission is rounded up, the shares always add up to the amount paid.
+    let mut paid = 0;
+    let mut commission = 0;
+    for price in [101_i128, 333_i128, 999_i128, 40_i128] {
+        auction_settings.ask_price = price;
+        let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+        auction_contract.place_bid(&auction_id, &buyer, &price, &None);
+        paid += price;
+        commission += (price * commission_rate + 9_999) / 10_000;
+        assert_eq!(market.balance(&token_admin), commission);
+        assert_eq!(market.balance(&token_admin) + market.balance(&seller), paid);
+        assert_eq!(market.balance(&buyer), initial_balance - paid);
+    }
+
+    // Legacy admin data (commission rate in percent) is migrated to basis points.
+    env.as_contract(&auction_contract.address, || {
+        let mut admin_data = AdminData::load(&env);
+        admin_data.commission_rate = 10;
+        env.storage().instance().set(&DataKey::AdminData, &admin_data);
+        env.storage().instance().remove(&DataKey::StorageVersion);
+        assert_eq!(AdminData::load(&env).commission_rate, 1000);
+        assert_eq!(AdminData::load(&env).commission_rate, 1000);
+    });
+
+    let price = 500;
+    auction_settings.ask_price = price;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_bid(&auction_id, &buyer, &price, &None);
+    assert_eq!(market.balance(&token_admin), commission + 50);
+    assert_eq!(market.balance(&token_admin) + market.balance(&seller), paid + price);
+}
+
+#[test]
+fn test_auction_indexes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let sellers = [Address::generate(&env), Address::generate(&env)];
+    let buyer = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (other_token, other_token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+
+    token_admin_client.mint(&sellers[0], &2);
+    other_token_admin_client.mint(&sellers[1], &1);
+    market_admin_client.mint(&buyer, &1000);
+    auction_contract.initialize(&token_admin, &0, &0, &false);
+
+    let auction_settings: AuctionSettings = AuctionSettings {
+        seller: sellers[0].clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 10,
+        ask_price: 500,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // No auctions yet.
+    assert_eq!(auction_contract.list_auctions(&None, &10).len(), 0);
+
+    let first_id = start_auction(&env, &auction_contract, &auction_settings);
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    let second_id = start_auction(&env, &auction_contract, &auction_settings);
+    let third_id = start_auction(&env, &auction_contract, &AuctionSettings {
+        seller: sellers[1].clone(),
+        token: other_token.address.clone(),
+        ..auction_settings.clone()
+    });
+
+    // Paginate all active auctions.
+    let page = auction_contract.list_auctions(&None, &2);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get_unchecked(0).id, first_id);
+    assert_eq!(page.get_unchecked(1).id, second_id);
+    let page = auction_contract.list_auctions(&Some(second_id), &2);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get_unchecked(0).id, third_id);
+    assert_eq!(auction_contract.list_auctions(&Some(third_id), &2).len(), 0);
+    assert_eq!(auction_contract.list_auctions(&None, &1000).len(), 3);
+
+    // Query by seller, token and market.
+    assert_eq!(auction_contract.auctions_by_seller(&sellers[0], &None, &10).len(), 2);
+    assert_eq!(auction_contract.auctions_by_seller(&sellers[1], &None, &10).get_unchecked(0).id, third_id);
+    assert_eq!(auction_contract.auctions_by_token(&token.address, &None, &10).len(), 2);
+    assert_eq!(auction_contract.auctions_by_token(&other_token.address, &None, &10).len(), 1);
+    assert_eq!(auction_contract.auctions_by_market(&market.address, &None, &10).len(), 3);
+
+    // Won and expired auctions are removed from the indexes.
+    auction_contract.place_bid(&second_id, &buyer, &500, &None);
+    env.ledger().with_mut(|li| li.timestamp = 120);
+    auction_contract.resolve(&first_id);
+    assert!(auction_contract.get_auction(&first_id).is_none());
+    let page = auction_contract.list_auctions(&None, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get_unchecked(0).id, third_id);
+    assert_eq!(auction_contract.auctions_by_seller(&sellers[0], &None, &10).len(), 0);
+    assert_eq!(auction_contract.auctions_by_token(&token.address, &None, &10).len(), 0);
+    assert_eq!(auction_contract.auctions_by_market(&market.address, &None, &10).len(), 1);
+
+    // Cursors remain valid after their auction is removed.
+    other_token_admin_client.mint(&sellers[1], &1);
+    assert_eq!(auction_contract.list_auctions(&Some(first_id), &10).get_unchecked(0).id, third_id);
+    assert_eq!(auction_contract.list_auctions(&Some(second_id), &10).get_unchecked(0).id, third_id);
+    let fourth_id = start_auction(&env, &auction_contract, &AuctionSettings {
+        seller: sellers[1].clone(),
+        token: other_token.address.clone(),
+        ..auction_settings.clone()
+    });
+    let page = auction_contract.list_auctions(&Some(third_id), &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get_unchecked(0).id, fourth_id);
+    let page = auction_contract.auctions_by_seller(&sellers[1], &None, &1);
+    assert_eq!(page.get_unchecked(0).id, third_id);
+    auction_contract.cancel(&third_id);
+    let page = auction_contract.auctions_by_seller(&sellers[1], &Some(third_id), &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get_unchecked(0).id, fourth_id);
+    assert_eq!(auction_contract.list_auctions(&None, &10).get_unchecked(0).id, fourth_id);
+
+    // Expired entries of abandoned auctions end the pages and are left unlinked.
+    other_token_admin_client.mint(&sellers[1], &3);
+    let other_settings = AuctionSettings { seller: sellers[1].clone(), token: other_token.address.clone(), ..auction_settings.clone() };
+    let fifth_id = start_auction(&env, &auction_contract, &other_settings);
+    let sixth_id = start_auction(&env, &auction_contract, &other_settings);
+    env.as_contract(&auction_contract.address, || {
+        env.storage().persistent().remove(&DataKey::AuctionData(fifth_id));
+        for index in [
+            AuctionIndex::Active,
+            AuctionIndex::Seller(sellers[1].clone()),
+            AuctionIndex::Token(other_token.address.clone()),
+            AuctionIndex::Market(market.address.clone()),
+        ] {
+            env.storage().persistent().remove(&DataKey::IndexNode(index, fifth_id));
+        }
+    });
+    let page = auction_contract.list_auctions(&None, &10);
+    assert_eq!(page.len(), 1);
+    assert_eq!(page.get_unchecked(0).id, fourth_id);
+    auction_contract.cancel(&sixth_id);
+    let seventh_id = start_auction(&env, &auction_contract, &other_settings);
+    assert_eq!(auction_contract.list_auctions(&None, &10).last_unchecked().id, seventh_id);
+}
+
+#[test]
+fn test_cancel_auctions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+
+    token_admin_client.mint(&seller, &1);
+    market_admin_client.mint(&seller, &initial_balance);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+    auction_contract.initialize(&token_admin, &0, &0, &false);
+
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 10,
+        sealed_bid_deposit: 20,
+        reveal_phase_time: 10,
+        kind: AuctionKind::SealedBid,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // Sealed bid deposits are refunded on cancellation.
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    for (i, bidder) in bidders.iter().enumerate() {
+        auction_contract.place_sealed_bid(&auction_id, bidder, &BytesN::from_array(&env, &[i as u8; 32]));
+        assert_eq!(market.balance(bidder), initial_balance - auction_settings.sealed_bid_deposit);
+    }
+    auction_contract.cancel(&auction_id);
+    assert!(auction_contract.get_auction(&auction_id).is_none());
+    assert_eq!(auction_contract.list_auctions(&None, &10).len(), 0);
+    assert_eq!(token.balance(&seller), 1);
+    for bidder in bidders.iter() {
+        assert_eq!(market.balance(bidder), initial_balance);
+    }
+    assert_eq!(market.balance(&auction_contract.address), 0);
+
+    // Bids meeting the reserve prevent cancellation by default.
+    auction_settings.kind = AuctionKind::Ascending;
+    auction_settings.sealed_phase_time = 0;
+    auction_settings.sealed_bid_deposit = 0;
+    auction_settings.reveal_phase_time = 0;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_bid(&auction_id, &bidders[0], &150, &None);
+    auction_contract.place_bid(&auction_id, &bidders[1], &200, &None);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.cancel(&auction_id);
+    }));
+    assert!(result.is_err(), "Should panic. Auction has bids.");
+
+    // Cancellation with penalty (5% of the highest bid) to the admin.
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.set_cancellation_policy(&CancellationPolicy { allow_with_bids: true, penalty_rate: 10001 });
+    }));
+    assert!(result.is_err(), "Should panic. Invalid penalty rate.");
+    auction_contract.set_cancellation_policy(&CancellationPolicy { allow_with_bids: true, penalty_rate: 500 });
+    auction_contract.cancel(&auction_id);
+    assert!(auction_contract.get_auction(&auction_id).is_none());
+    assert_eq!(token.balance(&seller), 1);
+    assert_eq!(market.balance(&seller), initial_balance - 10);
+    assert_eq!(market.balance(&token_admin), 10);
+    for bidder in bidders.iter() {
+        assert_eq!(market.balance(bidder), initial_balance);
+    }
+    assert_eq!(market.balance(&auction_contract.address), 0);
+
+    // Ended auctions can no longer be cancelled, they are settled to the winner.
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_bid(&auction_id, &bidders[0], &150, &None);
+    env.ledger().with_mut(|li| li.timestamp += auction_settings.duration + 1);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.cancel(&auction_id);
+    }));
+    assert!(result.is_err(), "Should panic. Auction has ended.");
+    assert_eq!(auction_contract.resolve(&auction_id).unwrap().outcome, OutcomeKind::Won);
+    assert_eq!(token.balance(&bidders[0]), 1);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - 150);
+}
+
+#[test]
+fn test_bid_increments() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 10000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+
+    token_admin_client.mint(&seller, &2);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+    auction_contract.initialize(&token_admin, &0, &0, &false);
+
+    // Starting price below the reserve and absolute increment.
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 1000,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Descending,
+        starting_price: 500,
+        min_increment: 100,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // Outbid rules are only supported for ascending auctions.
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        start_auction(&env, &auction_contract, &auction_settings);
+    }));
+    assert!(result.is_err(), "Should panic. Outbid rules not supported.");
+
+    auction_settings.kind = AuctionKind::Ascending;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    assert_eq!(auction_contract.get_min_bid(&auction_id), 500);
+
+    // Bids below the starting price or not beating the leader by the increment are rejected.
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[0], &499, &None);
+    }));
+    assert!(result.is_err(), "Should panic. Below starting price.");
+    auction_contract.place_bid(&auction_id, &bidders[0], &500, &None);
+    assert_eq!(auction_contract.get_min_bid(&auction_id), 600);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[1], &599, &None);
+    }));
+    assert!(result.is_err(), "Should panic. Increment not met.");
+    auction_contract.place_bid(&auction_id, &bidders[1], &600, &None);
+    assert_eq!(auction_contract.get_min_bid(&auction_id), 700);
+
+    // Reserve price is not met, no winner.
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    auction_contract.resolve(&auction_id);
+    assert!(auction_contract.get_auction(&auction_id).is_none());
+    assert_eq!(token.balance(&seller), 2);
+    assert_eq!(market.balance(&bidders[1]), initial_balance);
+
+    // Relative increment (10% of the leading bid), opening at the reserve price.
+    auction_settings.starting_price = 0;
+    auction_settings.min_increment = 1000;
+    auction_settings.relative_increment = true;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    assert_eq!(auction_contract.get_min_bid(&auction_id), 1000);
+    auction_contract.place_bid(&auction_id, &bidders[0], &1000, &None);
+    assert_eq!(auction_contract.get_min_bid(&auction_id), 1100);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[1], &1099, &None);
+    }));
+    assert!(result.is_err(), "Should panic. Increment not met.");
+    auction_contract.place_bid(&auction_id, &bidders[1], &1234, &None);
+    assert_eq!(auction_contract.get_min_bid(&auction_id), 1358);
+    auction_contract.place_bid(&auction_id, &bidders[2], &1358, &None);
+
+    // Highest bid wins.
+    env.ledger().with_mut(|li| li.timestamp = 202);
+    auction_contract.resolve(&auction_id);
+    assert_eq!(token.balance(&bidders[2]), 1);
+    assert_eq!(market.balance(&bidders[2]), initial_balance - 1358);
+    assert_eq!(market.balance(&bidders[0]), initial_balance);
+    assert_eq!(market.balance(&bidders[1]), initial_balance);
+}
+
+#[test]
+fn test_raise_bid() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let anti_snipe_time = 60;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &1);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &anti_snipe_time, &commission_rate, &true);
+
+    // Configure an ascending price auction (English auction).
+    let auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 180,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 900,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_bid(&auction_id, &bidders[0], &100, &None);
+    auction_contract.place_bid(&auction_id, &bidders[1], &150, &None);
+
+    // Raising escrows the difference only and updates the bid in place.
+    auction_contract.raise_bid(&auction_id, &bidders[0], &200);
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert_eq!(auction_data.bids.len(), 2);
+    assert_eq!(auction_data.bids.get_unchecked(0).amount, 200);
+    assert!(!auction_data.bids.get_unchecked(0).sniper);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - 200);
+
+    // Cannot lower a bid or raise a bid that does not exist.
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.raise_bid(&auction_id, &bidders[0], &200);
+    }));
+    assert!(result.is_err(), "Should panic. Bid not raised.");
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.raise_bid(&auction_id, &bidders[2], &500);
+    }));
+    assert!(result.is_err(), "Should panic. No bid to raise.");
+
+    // A late bid is flagged as sniper and cannot be cancelled, but can be raised.
+    env.ledger().with_mut(|li| li.timestamp = 150);
+    auction_contract.place_bid(&auction_id, &bidders[1], &0, &None);
+    auction_contract.place_bid(&auction_id, &bidders[1], &250, &None);
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert!(auction_data.bids.get_unchecked(1).sniper);
+    assert_eq!(auction_data.settings.duration, 240);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[1], &0, &None);
+    }));
+    assert!(result.is_err(), "Should panic. Sniper bid cannot be cancelled.");
+    auction_contract.raise_bid(&auction_id, &bidders[1], &300);
+    assert_eq!(market.balance(&bidders[1]), initial_balance - 300);
+
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert_eq!(auction_data.settings.duration, 240);
+
+    // Raising within the anti-snipe window extends the auction again.
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    auction_contract.raise_bid(&auction_id, &bidders[0], &350);
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert!(auction_data.bids.get_unchecked(0).sniper);
+    assert_eq!(auction_data.settings.duration, 300);
+
+    // Highest bid wins, the other bidder is refunded in full.
+    env.ledger().with_mut(|li| li.timestamp = 301);
+    auction_contract.resolve(&auction_id);
+    assert_eq!(token.balance(&bidders[0]), 1);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - 350);
+    assert_eq!(market.balance(&bidders[1]), initial_balance);
+    assert_eq!(market.balance(&seller), 315);
+    assert_eq!(market.balance(&token_admin), 35);
+}
+
+#[test]
+fn test_proxy_bids() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &2);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &true);
+
+    // Configure a descending price auction (Dutch auction).
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 900,
+        discount_percent: 10,
+        discount_frequency: 20,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Descending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // Proxy bids are only supported for ascending auctions.
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_proxy_bid(&auction_id, &bidders[0], &500);
+    }));
+    assert!(result.is_err(), "Should panic. Proxy bids not supported.");
+    auction_contract.cancel(&auction_id);
+
+    auction_settings.kind = AuctionKind::Ascending;
+    auction_settings.discount_percent = 0;
+    auction_settings.discount_frequency = 0;
+    auction_settings.min_increment = 10;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+
+    // The full maximum is escrowed, the visible price opens at the reserve.
+    auction_contract.place_proxy_bid(&auction_id, &bidders[0], &500);
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert_eq!(auction_data.bids.get_unchecked(0).amount, 100);
+    assert_eq!(auction_data.bids.get_unchecked(0).max_amount, 500);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - 500);
+
+    // The proxy outbids a regular bid by the increment.
+    auction_contract.place_bid(&auction_id, &bidders[1], &150, &None);
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert_eq!(auction_data.bids.get_unchecked(0).amount, 160);
+    assert_eq!(auction_contract.get_min_bid(&auction_id), 170);
+
+    // Proxy bids report their new visible price.
+    assert!(env.events().all().iter().any(|(_, topics, data)| {
+        Symbol::try_from_val(&env, &topics.get_unchecked(1)).is_ok_and(|topic| topic == Symbol::new(&env, "repriced"))
+            && BidPlaced::try_from_val(&env, &data).unwrap() == BidPlaced {
+                version: EVENTS_VERSION,
+                auction_id,
+                buyer: bidders[0].clone(),
+                market: market.address.clone(),
+                amount: 160,
+                max_amount: 500,
+                quantity: 1,
+                sniper: false,
+            }
+    }));
+
+    // A lower proxy is outbid at its maximum.
+    auction_contract.place_proxy_bid(&auction_id, &bidders[2], &300);
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert_eq!(auction_data.bids.get_unchecked(0).amount, 310);
+    assert_eq!(auction_data.bids.get_unchecked(2).amount, 300);
+
+    // Raising the maximum takes the lead, escrowing the difference only.
+    auction_contract.raise_bid(&auction_id, &bidders[2], &600);
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert_eq!(auction_data.bids.get_unchecked(0).amount, 500);
+    assert_eq!(auction_data.bids.get_unchecked(2).amount, 510);
+    assert_eq!(auction_data.bids.get_unchecked(2).max_amount, 600);
+    assert_eq!(market.balance(&bidders[2]), initial_balance - 600);
+
+    // The winner pays the clearing price and gets the unused escrow back.
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    auction_contract.resolve(&auction_id);
+    assert_eq!(token.balance(&bidders[2]), 1);
+    assert_eq!(market.balance(&bidders[2]), initial_balance - 510);
+    assert_eq!(market.balance(&bidders[0]), initial_balance);
+    assert_eq!(market.balance(&bidders[1]), initial_balance);
+    assert_eq!(market.balance(&seller), 459);
+    assert_eq!(market.balance(&token_admin), 51);
+}
+
+#[test]
+fn test_soft_close_policy() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &1);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &60, &commission_rate, &true);
+
+    // Configure an ascending price auction (English auction).
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 900,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 120, extension: 50, max_extensions: 2 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // The policy is bounded by the admin limits (anti_snipe_time by default).
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        start_auction(&env, &auction_contract, &auction_settings);
+    }));
+    assert!(result.is_err(), "Should panic. Window exceeds the admin limits.");
+    auction_contract.set_soft_close_limits(&SoftClosePolicy { window: 300, extension: 300, max_extensions: 5 });
+    auction_settings.soft_close.window = 30;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+
+    // Bids outside the window do not extend the auction.
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    auction_contract.place_bid(&auction_id, &bidders[0], &100, &None);
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert!(!auction_data.bids.get_unchecked(0).sniper);
+    assert_eq!(auction_data.settings.duration, 100);
+
+    // Late bids extend the auction by the policy extension and report the new end time.
+    env.ledger().with_mut(|li| li.timestamp = 80);
+    auction_contract.place_bid(&auction_id, &bidders[1], &110, &None);
+    assert!(env.events().all().iter().any(|(_, topics, data)| {
+        Symbol::try_from_val(&env, &topics.get_unchecked(1)).is_ok_and(|topic| topic == Symbol::new(&env, "extended"))
+            && AuctionExtended::try_from_val(&env, &data).unwrap() == AuctionExtended {
+                version: EVENTS_VERSION,
+                auction_id,
+                end_time: 150,
+                extensions: 1,
+            }
+    }));
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert!(auction_data.bids.get_unchecked(1).sniper);
+    assert_eq!(auction_data.settings.duration, 150);
+    assert_eq!(auction_data.extensions, 1);
+
+    // Snipers can still raise their bid.
+    env.ledger().with_mut(|li| li.timestamp = 140);
+    auction_contract.raise_bid(&auction_id, &bidders[0], &130);
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert_eq!(auction_data.settings.duration, 200);
+    assert_eq!(auction_data.extensions, 2);
+
+    // No more extensions once the maximum is reached.
+    env.ledger().with_mut(|li| li.timestamp = 190);
+    auction_contract.raise_bid(&auction_id, &bidders[1], &150);
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert_eq!(auction_data.settings.duration, 200);
+    assert_eq!(auction_data.extensions, 2);
+
+    env.ledger().with_mut(|li| li.timestamp = 201);
+    auction_contract.resolve(&auction_id);
+    assert_eq!(token.balance(&bidders[1]), 1);
+    assert_eq!(market.balance(&bidders[1]), initial_balance - 150);
+    assert_eq!(market.balance(&bidders[0]), initial_balance);
+}
+
+#[test]
+fn test_deposit_forfeiture() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &2);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &true);
+
+    // 20% of the forfeited deposits go to the admin.
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.set_forfeiture_rate(&10001);
+    }));
+    assert!(result.is_err(), "Should panic. Invalid forfeiture rate.");
+    auction_contract.set_forfeiture_rate(&2000);
+
+    // Configure a second price sealed bid auction (Vickrey auction).
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 50,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 10,
+        sealed_bid_deposit: 10,
+        reveal_phase_time: 50,
+        kind: AuctionKind::SecondPrice,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // The reveal window must close within the auction duration.
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        start_auction(&env, &auction_contract, &auction_settings);
+    }));
+    assert!(result.is_err(), "Should panic. Reveal window exceeds duration.");
+    auction_settings.reveal_phase_time = 20;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+
+    // Submit the sealed bids.
+    let amounts = [300_i128, 200_i128, 400_i128];
+    let salt = BytesN::from_array(&env, &[0_u8; 32]);
+    for (bidder, amount) in bidders.iter().zip(amounts.iter()) {
+        let mut sealed_data = Bytes::from_array(&env, &amount.to_be_bytes());
+        sealed_data.append(&Bytes::from_slice(&env, &salt.to_array()));
+        sealed_data.append(&Bytes::from_slice(&env, &auction_id.to_be_bytes()));
+        sealed_data.append(&bidder.clone().to_xdr(&env));
+        auction_contract.place_sealed_bid(&auction_id, bidder, &env.crypto().sha256(&sealed_data));
+    }
+
+    // Reveal within the window, the last bidder misses the deadline.
+    // Reveals remain available while paused.
+    env.ledger().with_mut(|li| li.timestamp = 15);
+    auction_contract.pause();
+    for (bidder, amount) in bidders.iter().zip(amounts.iter()).take(2) {
+        auction_contract.place_bid(&auction_id, bidder, amount, &Some(salt.clone()));
+    }
+    auction_contract.unpause();
+    env.ledger().with_mut(|li| li.timestamp = 31);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[2], &amounts[2], &Some(salt.clone()));
+    }));
+    assert!(result.is_err(), "Should panic. Reveal window closed.");
+
+    // The winner pays the second price, the unrevealed deposit is forfeited.
+    env.ledger().with_mut(|li| li.timestamp = 51);
+    auction_contract.resolve(&auction_id);
+    assert!(auction_contract.get_auction(&auction_id).is_none());
+    assert_eq!(token.balance(&bidders[0]), 1);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - 200);
+    assert_eq!(market.balance(&bidders[1]), initial_balance);
+    assert_eq!(market.balance(&bidders[2]), initial_balance - 10);
+    assert_eq!(market.balance(&token_admin), 20 + 2);
+    assert_eq!(market.balance(&seller), 180 + 8);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+
+    // Deposits are also forfeited when the auction ends without a winner.
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_sealed_bid(&auction_id, &bidders[1], &BytesN::from_array(&env, &[1_u8; 32]));
+    env.ledger().with_mut(|li| li.timestamp = 102);
+    auction_contract.resolve(&auction_id);
+    assert!(auction_contract.get_auction(&auction_id).is_none());
+    assert_eq!(token.balance(&seller), 1);
+    assert_eq!(market.balance(&bidders[1]), initial_balance - 10);
+    assert_eq!(market.balance(&token_admin), 22 + 2);
+    assert_eq!(market.balance(&seller), 188 + 8);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+}
+
+#[test]
+fn test_auction_phases() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &2);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &true);
+
+    // Configure a sealed bid auction.
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 50,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 10,
+        sealed_bid_deposit: 10,
+        reveal_phase_time: 10,
+        kind: AuctionKind::SealedBid,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    assert_eq!(auction_contract.get_phase(&0), None);
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    assert_eq!(auction_contract.get_phase(&auction_id), Some(AuctionPhase::Committing));
+
+    let salt = BytesN::from_array(&env, &[0_u8; 32]);
+    let mut sealed_data = Bytes::from_array(&env, &150_i128.to_be_bytes());
+    sealed_data.append(&Bytes::from_slice(&env, &salt.to_array()));
+    sealed_data.append(&Bytes::from_slice(&env, &auction_id.to_be_bytes()));
+    sealed_data.append(&bidders[0].clone().to_xdr(&env));
+    auction_contract.place_sealed_bid(&auction_id, &bidders[0], &env.crypto().sha256(&sealed_data));
+
+    // Time-based transitions can be triggered by anyone.
+    env.ledger().with_mut(|li| li.timestamp = 10);
+    assert_eq!(auction_contract.advance(&auction_id), AuctionPhase::Revealing);
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_sealed_bid(&auction_id, &bidders[1], &BytesN::from_array(&env, &[1_u8; 32]));
+    }));
+    assert!(result.is_err(), "Should panic. Commit phase is over.");
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.raise_bid(&auction_id, &bidders[0], &200);
+    }));
+    assert!(result.is_err(), "Should panic. Auction is not running.");
+    auction_contract.place_bid(&auction_id, &bidders[0], &150, &Some(salt));
+
+    // Running until settlement.
+    env.ledger().with_mut(|li| li.timestamp = 21);
+    assert_eq!(auction_contract.advance(&auction_id), AuctionPhase::Running);
+    env.ledger().with_mut(|li| li.timestamp = 51);
+    auction_contract.resolve(&auction_id);
+    assert_eq!(auction_contract.get_phase(&auction_id), Some(AuctionPhase::Settled));
+    assert_eq!(token.balance(&bidders[0]), 1);
+
+    // Scheduled auctions do not accept bids nor resolve before their start time.
+    auction_settings.kind = AuctionKind::Ascending;
+    auction_settings.sealed_phase_time = 0;
+    auction_settings.sealed_bid_deposit = 0;
+    auction_settings.reveal_phase_time = 0;
+    auction_settings.start_time = 100;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    assert_eq!(auction_contract.advance(&auction_id), AuctionPhase::Scheduled);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[1], &150, &None);
+    }));
+    assert!(result.is_err(), "Should panic. Auction is scheduled.");
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    auction_contract.resolve(&auction_id);
+    assert_eq!(auction_contract.get_phase(&auction_id), Some(AuctionPhase::Settled));
+
+    // Cancelled auctions.
+    auction_settings.start_time = 0;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.cancel(&auction_id);
+    assert_eq!(auction_contract.get_phase(&auction_id), Some(AuctionPhase::Cancelled));
+    assert_eq!(token.balance(&seller), 1);
+}
+
+#[test]
+fn test_sealed_bid_replay() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &1);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &true);
+
+    // Configure a sealed bid auction.
+    let auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 50,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 10,
+        sealed_bid_deposit: 10,
+        reveal_phase_time: 10,
+        kind: AuctionKind::SealedBid,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+
+    let amount = 300_i128;
+    let salt = BytesN::from_array(&env, &[7_u8; 32]);
+    let seal = |buyer: &Address| {
+        let mut sealed_data = Bytes::from_array(&env, &amount.to_be_bytes());
+        sealed_data.append(&Bytes::from_slice(&env, &salt.to_array()));
+        sealed_data.append(&Bytes::from_slice(&env, &auction_id.to_be_bytes()));
+        sealed_data.append(&buyer.clone().to_xdr(&env));
+        env.crypto().sha256(&sealed_data)
+    };
+
+    // Commitments are stored per bidder, copying one does not block the original bidder.
+    auction_contract.place_sealed_bid(&auction_id, &bidders[1], &seal(&bidders[0]));
+    auction_contract.place_sealed_bid(&auction_id, &bidders[0], &seal(&bidders[0]));
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_sealed_bid(&auction_id, &bidders[0], &seal(&bidders[0]));
+    }));
+    assert!(result.is_err(), "Should panic. Already committed.");
+
+    // The copied commitment cannot be revealed by another bidder.
+    env.ledger().with_mut(|li| li.timestamp = 10);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[1], &amount, &Some(salt.clone()));
+    }));
+    assert!(result.is_err(), "Should panic. Commitment bound to another bidder.");
+
+    // The reveal only releases the deposit of the committer, and cannot be replayed.
+    auction_contract.place_bid(&auction_id, &bidders[0], &amount, &Some(salt.clone()));
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert_eq!(auction_data.deposits.len(), 1);
+    assert_eq!(auction_data.deposits.get_unchecked(0).buyer, bidders[1]);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - amount);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[0], &amount, &Some(salt.clone()));
+    }));
+    assert!(result.is_err(), "Should panic. Commitment already revealed.");
+
+    // The copied commitment's deposit is forfeited.
+    env.ledger().with_mut(|li| li.timestamp = 51);
+    auction_contract.resolve(&auction_id);
+    assert_eq!(token.balance(&bidders[0]), 1);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - amount);
+    assert_eq!(market.balance(&bidders[1]), initial_balance - auction_settings.sealed_bid_deposit);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+}
+
+#[test]
+fn test_hidden_reserve() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &3);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &true);
+
+    // Configure an ascending price auction (English auction).
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // Reserve is sealed with sha256([big_endian_reserve;16][salt;32]).
+    let salt = BytesN::from_array(&env, &[3_u8; 32]);
+    let seal = |reserve: i128| {
+        let mut sealed_data = Bytes::from_array(&env, &reserve.to_be_bytes());
+        sealed_data.append(&Bytes::from_slice(&env, &salt.to_array()));
+        env.crypto().sha256(&sealed_data)
+    };
+
+    // The public reserve must be unset.
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.start_with_hidden_reserve(&auction_settings, &seal(250));
+    }));
+    assert!(result.is_err(), "Should panic. Public reserve price set.");
+    auction_settings.reserve_price = 0;
+    let auction_id = auction_contract.start_with_hidden_reserve(&auction_settings, &seal(250));
+    assert_eq!(auction_contract.get_auction(&auction_id).unwrap().settings.reserve_price, 0);
+    auction_contract.place_bid(&auction_id, &bidders[0], &120, &None);
+    auction_contract.place_bid(&auction_id, &bidders[1], &200, &None);
+
+    // The reserve can only be revealed at or after the end.
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.reveal_reserve(&auction_id, &250, &salt);
+    }));
+    assert!(result.is_err(), "Should panic. Auction not ended.");
+
+    // Settlement waits for the reveal.
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    auction_contract.resolve(&auction_id);
+    assert!(auction_contract.get_auction(&auction_id).is_some());
+
+    // No bids are accepted once the auction has ended.
+    let late_bidder = Address::generate(&env);
+    market_admin_client.mint(&late_bidder, &initial_balance);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &late_bidder, &300, &None);
+    }));
+    assert!(result.is_err(), "Should panic. Auction has ended.");
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_proxy_bid(&auction_id, &late_bidder, &300);
+    }));
+    assert!(result.is_err(), "Should panic. Auction has ended.");
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.raise_bid(&auction_id, &bidders[0], &300);
+    }));
+    assert!(result.is_err(), "Should panic. Auction has ended.");
+    assert_eq!(market.balance(&late_bidder), initial_balance);
+    assert_eq!(auction_contract.get_auction(&auction_id).unwrap().settings.duration, 100);
+
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.reveal_reserve(&auction_id, &150, &salt);
+    }));
+    assert!(result.is_err(), "Should panic. Reserve does not match the commitment.");
+
+    // Revealed reserve is not met, no winner.
+    auction_contract.reveal_reserve(&auction_id, &250, &salt);
+    assert_eq!(auction_contract.get_phase(&auction_id), Some(AuctionPhase::Settled));
+    assert_eq!(token.balance(&seller), 3);
+    assert_eq!(market.balance(&bidders[1]), initial_balance);
+
+    // Revealed reserve is met, highest bid wins.
+    let auction_id = auction_contract.start_with_hidden_reserve(&auction_settings, &seal(150));
+    auction_contract.place_bid(&auction_id, &bidders[0], &120, &None);
+    auction_contract.place_bid(&auction_id, &bidders[1], &200, &None);
+    env.ledger().with_mut(|li| li.timestamp = 202);
+    auction_contract.reveal_reserve(&auction_id, &150, &salt);
+    assert_eq!(token.balance(&bidders[1]), 1);
+    assert_eq!(market.balance(&bidders[1]), initial_balance - 200);
+    assert_eq!(market.balance(&bidders[0]), initial_balance);
+
+    // Reserve not revealed in time, highest bid wins regardless of the reserve.
+    let auction_id = auction_contract.start_with_hidden_reserve(&auction_settings, &seal(500));
+    auction_contract.place_bid(&auction_id, &bidders[0], &120, &None);
+    env.ledger().with_mut(|li| li.timestamp = 202 + 100 + 3601);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.reveal_reserve(&auction_id, &500, &salt);
+    }));
+    assert!(result.is_err(), "Should panic. Reveal deadline passed.");
+    auction_contract.resolve(&auction_id);
+    assert_eq!(token.balance(&bidders[0]), 1);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - 120);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+}
+
+#[test]
+fn test_scheduled_auctions() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &2);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &true);
+
+    // Configure a descending price auction (Dutch auction) scheduled in the future.
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 180,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 900,
+        discount_percent: 10,
+        discount_frequency: 20,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Descending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 500,
+        markets: vec![&env],
+    };
+
+    // Start time cannot be in the past.
+    env.ledger().with_mut(|li| li.timestamp = 1000);
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        start_auction(&env, &auction_contract, &auction_settings);
+    }));
+    assert!(result.is_err(), "Should panic. Start time in the past.");
+
+    // Token is escrowed right away.
+    auction_settings.start_time = 2000;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    assert_eq!(token.balance(&seller), 1);
+    assert_eq!(token.balance(&auction_contract.address), 1);
+    assert_eq!(auction_contract.get_auction(&auction_id).unwrap().start_time, 2000);
+    assert_eq!(auction_contract.get_phase(&auction_id), Some(AuctionPhase::Scheduled));
+
+    // Bids are refused until the start time.
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&auction_id, &bidders[0], &900, &None);
+    }));
+    assert!(result.is_err(), "Should panic. Auction is scheduled.");
+    auction_contract.resolve(&auction_id);
+    assert!(auction_contract.get_auction(&auction_id).is_some());
+
+    // Discount is measured from the scheduled start.
+    env.ledger().with_mut(|li| li.timestamp = 2039);
+    assert_eq!(auction_contract.advance(&auction_id), AuctionPhase::Running);
+    auction_contract.place_bid(&auction_id, &bidders[0], &800, &None);
+    assert!(auction_contract.get_auction(&auction_id).is_some());
+    env.ledger().with_mut(|li| li.timestamp = 2040);
+    auction_contract.resolve(&auction_id);
+    assert_eq!(token.balance(&bidders[0]), 1);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - 800);
+
+    // Sealed commitments are refused until the start time.
+    auction_settings.kind = AuctionKind::SealedBid;
+    auction_settings.ask_price = 0;
+    auction_settings.discount_percent = 0;
+    auction_settings.discount_frequency = 0;
+    auction_settings.sealed_phase_time = 10;
+    auction_settings.sealed_bid_deposit = 10;
+    auction_settings.reveal_phase_time = 10;
+    auction_settings.start_time = 3000;
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    assert_eq!(token.balance(&seller), 0);
+
+    let salt = BytesN::from_array(&env, &[0_u8; 32]);
+    let mut sealed_data = Bytes::from_array(&env, &150_i128.to_be_bytes());
+    sealed_data.append(&Bytes::from_slice(&env, &salt.to_array()));
+    sealed_data.append(&Bytes::from_slice(&env, &auction_id.to_be_bytes()));
+    sealed_data.append(&bidders[1].clone().to_xdr(&env));
+    let sealed_bid = env.crypto().sha256(&sealed_data);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_sealed_bid(&auction_id, &bidders[1], &sealed_bid);
+    }));
+    assert!(result.is_err(), "Should panic. Auction is scheduled.");
+
+    // Sealed phases are measured from the scheduled start.
+    env.ledger().with_mut(|li| li.timestamp = 3000);
+    auction_contract.place_sealed_bid(&auction_id, &bidders[1], &sealed_bid);
+    env.ledger().with_mut(|li| li.timestamp = 3010);
+    auction_contract.place_bid(&auction_id, &bidders[1], &150, &Some(salt));
+    env.ledger().with_mut(|li| li.timestamp = 3181);
+    auction_contract.resolve(&auction_id);
+    assert_eq!(token.balance(&bidders[1]), 1);
+    assert_eq!(market.balance(&bidders[1]), initial_balance - 150);
+}
+
+#[test]
+fn test_multi_currency_bids() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let (other_market, other_market_admin_client) = create_token_contract(&env, &token_admin);
+    let (unlisted_market, _) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &1);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+        other_market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &true);
+
+    // Configure an ascending price auction (English auction) accepting an additional currency.
+    let mut auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env, market.address.clone()],
+    };
+
+    // Additional currencies must differ from the auction market.
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        start_auction(&env, &auction_contract, &auction_settings);
+    }));
+    assert!(result.is_err(), "Should panic. Duplicate market.");
+    auction_settings.markets = vec![&env, other_market.address.clone()];
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    assert_eq!(auction_contract.auctions_by_market(&other_market.address, &None, &10).len(), 1);
+
+    // Bids are only accepted in listed currencies, with a price feed.
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid_with_market(&auction_id, &bidders[1], &unlisted_market.address, &500);
+    }));
+    assert!(result.is_err(), "Should panic. Currency not accepted.");
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid_with_market(&auction_id, &bidders[1], &other_market.address, &80);
+    }));
+    assert!(result.is_err(), "Should panic. No price feed.");
+
+    // Simulate an oracle price feed (1 other_market = 2 market).
+    auction_contract.test_oracle_feed(&other_market.address, &20_000_000, &market.address);
+
+    // Bids are ranked by their value in the auction market.
+    auction_contract.place_bid(&auction_id, &bidders[0], &150, &None);
+    auction_contract.place_bid_with_market(&auction_id, &bidders[1], &other_market.address, &80);
+    assert_eq!(auction_contract.get_min_bid(&auction_id), 161);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid_with_market(&auction_id, &bidders[2], &other_market.address, &80);
+    }));
+    assert!(result.is_err(), "Should panic. Bid value below the minimum bid.");
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_proxy_bid(&auction_id, &bidders[2], &500);
+    }));
+    assert!(result.is_err(), "Should panic. Proxy bids require a single currency.");
+    assert_eq!(other_market.balance(&bidders[1]), initial_balance - 80);
+    assert_eq!(other_market.balance(&auction_contract.address), 80);
+
+    // Raising is escrowed in the currency of the bid.
+    auction_contract.raise_bid(&auction_id, &bidders[0], &170);
+    assert_eq!(auction_contract.get_min_bid(&auction_id), 171);
+    assert_eq!(market.balance(&bidders[0]), initial_balance - 170);
+
+    // Prices older than the maximum age are rejected.
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.set_max_price_age(&0);
+    }));
+    assert!(result.is_err(), "Should panic. Invalid maximum age.");
+    auction_contract.set_max_price_age(&30);
+    env.ledger().with_mut(|li| li.timestamp = 50);
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid_with_market(&auction_id, &bidders[2], &other_market.address, &100);
+    }));
+    assert!(result.is_err(), "Should panic. Stale price feed.");
+
+    // Ranking follows the last price feed (1 other_market = 2.5 market).
+    auction_contract.test_oracle_feed(&other_market.address, &25_000_000, &market.address);
+    assert_eq!(auction_contract.get_min_bid(&auction_id), 201);
+
+    // Settlement does not wait for a fresh price feed, the last known price applies.
+    env.ledger().with_mut(|li| li.timestamp = 101);
+
+    // The seller is paid in the currency of the winning bid, other bids are refunded in their own.
+    auction_contract.resolve(&auction_id);
+    assert_eq!(token.balance(&bidders[1]), 1);
+    assert_eq!(other_market.balance(&bidders[1]), initial_balance - 80);
+    assert_eq!(other_market.balance(&token_admin), 8);
+    assert_eq!(other_market.balance(&seller), 72);
+    assert_eq!(market.balance(&bidders[0]), initial_balance);
+    assert_eq!(market.balance(&seller), 0);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+    assert_eq!(other_market.balance(&auction_contract.address), 0);
+}
+
+#[test]
+fn test_event_payloads() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &1);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &true);
+
+    // Configure an ascending price auction (English auction).
+    let auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // Event data are versioned structs, decoded by topic.
+    let find = |topic: &str| {
+        env.events().all().iter().find_map(|(_, topics, data)| {
+            match Symbol::try_from_val(&env, &topics.get_unchecked(1)) {
+                Ok(symbol) if symbol == Symbol::new(&env, topic) => Some(data),
+                _ => None,
+            }
+        }).unwrap()
+    };
+
+    env.ledger().with_mut(|li| li.timestamp = 10);
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    let started = AuctionStarted::try_from_val(&env, &find("started")).unwrap();
+    assert_eq!(started.version, EVENTS_VERSION);
+    assert_eq!(started.auction_id, auction_id);
+    assert_eq!(started.kind, AuctionKind::Ascending);
+    assert_eq!(started.seller, seller);
+    assert_eq!(started.end_time, 110);
+
+    auction_contract.place_bid(&auction_id, &bidders[0], &150, &None);
+    let added = BidPlaced::try_from_val(&env, &find("added")).unwrap();
+    assert_eq!(added.buyer, bidders[0]);
+    assert_eq!(added.market, market.address);
+    assert_eq!(added.amount, 150);
+    assert!(!added.sniper);
+
+    auction_contract.place_bid(&auction_id, &bidders[1], &200, &None);
+    auction_contract.raise_bid(&auction_id, &bidders[0], &250);
+    let raised = BidPlaced::try_from_val(&env, &find("raised")).unwrap();
+    assert_eq!((raised.buyer, raised.amount), (bidders[0].clone(), 250));
+
+    // Seller extensions report the new end time.
+    auction_contract.extend(&auction_id, &20);
+    let extended = AuctionExtended::try_from_val(&env, &find("extended")).unwrap();
+    assert_eq!((extended.auction_id, extended.end_time, extended.extensions), (auction_id, 130, 0));
+
+    // Settlement reports the winner, the shares and the refunds.
+    env.ledger().with_mut(|li| li.timestamp = 131);
+    auction_contract.resolve(&auction_id);
+    let settled = AuctionSettled::try_from_val(&env, &find("won")).unwrap();
+    assert_eq!(settled.auction_id, auction_id);
+    assert_eq!(settled.clearing_price, 250);
+    assert_eq!(settled.winners, vec![&env, Settlement {
+        buyer: bidders[0].clone(),
+        market: market.address.clone(),
+        quantity: 1,
+        amount: 250,
+        admin_share: 25,
+        royalty: 0,
+        seller_share: 225,
+    }]);
+    assert_eq!(settled.refunds, vec![&env, Payment {
+        recipient: bidders[1].clone(),
+        market: market.address.clone(),
+        amount: 200,
+    }]);
+    assert_eq!(market.balance(&seller), 225);
+    assert_eq!(market.balance(&token_admin), 25);
+}
+
+#[test]
+fn test_auction_outcomes() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let initial_balance = 1000;
+    let commission_rate = 1000;
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+
+    // Initialize the balances.
+    token_admin_client.mint(&seller, &3);
+    for bidder in bidders.iter() {
+        market_admin_client.mint(bidder, &initial_balance);
+    }
+
+    auction_contract.initialize(&token_admin, &0, &commission_rate, &true);
+
+    // Configure an ascending price auction (English auction).
+    let auction_settings: AuctionSettings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+
+    // No outcome while the auction is active.
+    env.ledger().with_mut(|li| li.timestamp = 0);
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_bid(&auction_id, &bidders[0], &150, &None);
+    auction_contract.place_bid(&auction_id, &bidders[1], &200, &None);
+    assert_eq!(auction_contract.resolve(&auction_id), None);
+    assert_eq!(auction_contract.get_outcome(&auction_id), None);
+
+    // Won auctions archive the settings summary, winner, price and fees.
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    let outcome = auction_contract.resolve(&auction_id).unwrap();
+    assert_eq!(auction_contract.get_auction(&auction_id), None);
+    assert_eq!(auction_contract.get_outcome(&auction_id), Some(outcome.clone()));
+    assert_eq!(outcome.id, auction_id);
+    assert_eq!(outcome.outcome, OutcomeKind::Won);
+    assert_eq!(outcome.kind, AuctionKind::Ascending);
+    assert_eq!(outcome.seller, seller);
+    assert_eq!(outcome.token, token.address);
+    assert_eq!(outcome.amount, 1);
+    assert_eq!(outcome.market, market.address);
+    assert_eq!(outcome.reserve_price, 100);
+    assert_eq!(outcome.clearing_price, 200);
+    assert_eq!(outcome.end_time, 100);
+    assert_eq!(outcome.settled_time, 101);
+    assert_eq!(outcome.winners, vec![&env, Settlement {
+        buyer: bidders[1].clone(),
+        market: market.address.clone(),
+        quantity: 1,
+        amount: 200,
+        admin_share: 20,
+        royalty: 0,
+        seller_share: 180,
+    }]);
+
+    // Auctions ending without winner.
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    env.ledger().with_mut(|li| li.timestamp = 202);
+    let outcome = auction_contract.resolve(&auction_id).unwrap();
+    assert_eq!(outcome.outcome, OutcomeKind::Ended);
+    assert!(outcome.winners.is_empty());
+    assert_eq!(outcome.clearing_price, 0);
+    assert_eq!((outcome.end_time, outcome.settled_time), (201, 202));
+
+    // Cancelled auctions.
+    let auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    env.ledger().with_mut(|li| li.timestamp = 250);
+    auction_contract.cancel(&auction_id);
+    let outcome = auction_contract.get_outcome(&auction_id).unwrap();
+    assert_eq!(outcome.outcome, OutcomeKind::Cancelled);
+    assert_eq!((outcome.end_time, outcome.settled_time), (302, 250));
+    assert_eq!(auction_contract.resolve(&auction_id), Some(outcome));
+
+    // Lots of several units clear at the winning amount.
+    let auction_id = start_auction(&env, &auction_contract, &AuctionSettings { amount: 2, ..auction_settings.clone() });
+    auction_contract.place_bid(&auction_id, &bidders[0], &205, &None);
+    env.ledger().with_mut(|li| li.timestamp = 351);
+    let outcome = auction_contract.resolve(&auction_id).unwrap();
+    assert_eq!(outcome.outcome, OutcomeKind::Won);
+    assert_eq!(outcome.clearing_price, 205);
+    assert_eq!(token.balance(&bidders[0]), 2);
+}
+
+#[test]
+fn test_admin_configuration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let new_admin = Address::generate(&env);
+    let auction_contract = create_auction_contract(&env);
+    auction_contract.initialize(&token_admin, &60, &1000, &true);
+    let admin_data = || env.as_contract(&auction_contract.address, || AdminData::load(&env));
+
+    // Admin settings are validated.
+    let mut result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.set_commission_rate(&10_001);
+    }));
+    assert!(result.is_err(), "Should panic. Invalid commission rate.");
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.set_anti_snipe_time(&61);
+    }));
+    assert!(result.is_err(), "Should panic. Invalid anti-snipe time.");
+
+    // Each setter requires the admin and publishes an event.
+    auction_contract.set_commission_rate(&250);
+    assert_eq!(env.auths()[0].0, token_admin);
+    let updated = env.events().all().last().unwrap().2;
+    assert_eq!(CommissionRateUpdated::try_from_val(&env, &updated).unwrap(), CommissionRateUpdated {
+        version: EVENTS_VERSION,
+        old_rate: 1000,
+        new_rate: 250,
+    });
+    auction_contract.set_anti_snipe_time(&30);
+    auction_contract.set_extendable_auctions(&false);
+    assert_eq!(admin_data(), AdminData {
+        admin: token_admin.clone(),
+        anti_snipe_time: 30,
+        commission_rate: 250,
+        extendable_auctions: false,
+    });
+
+    // Admin rights are only transferred once accepted by the proposed admin.
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.accept_admin();
+    }));
+    assert!(result.is_err(), "Should panic. No pending admin.");
+    auction_contract.propose_admin(&Address::generate(&env));
+    auction_contract.propose_admin(&new_admin);
+    assert_eq!(env.auths()[0].0, token_admin);
+    assert_eq!(admin_data().admin, token_admin);
+    auction_contract.accept_admin();
+    assert_eq!(env.auths()[0].0, new_admin);
+    let transferred = env.events().all().last().unwrap().2;
+    assert_eq!(AdminTransferred::try_from_val(&env, &transferred).unwrap(), AdminTransferred {
+        version: EVENTS_VERSION,
+        old_admin: token_admin.clone(),
+        new_admin: new_admin.clone(),
+    });
+    assert_eq!(admin_data().admin, new_admin);
+
+    // The proposal is consumed and the new admin manages the contract.
+    result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.accept_admin();
+    }));
+    assert!(result.is_err(), "Should panic. No pending admin.");
+    auction_contract.set_extendable_auctions(&true);
+    assert_eq!(env.auths()[0].0, new_admin);
+    assert!(admin_data().extendable_auctions);
+}
+
+#[test]
+fn test_roles() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let bidder = Address::generate(&env);
+    let [upgrader, operator, treasury, broker_manager, pauser] =
+        [0; 5].map(|_| Address::generate(&env));
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    token_admin_client.mint(&seller, &2);
+    market_admin_client.mint(&seller, &1000);
+    market_admin_client.mint(&bidder, &1000);
+    auction_contract.initialize(&token_admin, &60, &1000, &true);
+
+    // Roles are held by the admin until granted.
+    assert_eq!(auction_contract.get_role(&Role::Treasury), token_admin);
+    auction_contract.pause();
+    assert_eq!(env.auths()[0].0, token_admin);
+    auction_contract.unpause();
+
+    // Granting a role requires the admin and publishes an event.
+    for (role, account) in [
+        (Role::Upgrader, &upgrader),
+        (Role::ConfigOperator, &operator),
+        (Role::Treasury, &treasury),
+        (Role::BrokerManager, &broker_manager),
+        (Role::Pauser, &pauser),
+    ] {
+        auction_contract.grant_role(&role, account);
+        assert_eq!(env.auths()[0].0, token_admin);
+        assert_eq!(auction_contract.get_role(&role), *account);
+    }
+    let granted = env.events().all().last().unwrap().2;
+    assert_eq!(RoleUpdated::try_from_val(&env, &granted).unwrap(), RoleUpdated {
+        version: EVENTS_VERSION,
+        role: Role::Pauser,
+        account: pauser.clone(),
+    });
+
+    // Privileged entry points require their role holder.
+    auction_contract.pause();
+    assert_eq!(env.auths()[0].0, pauser);
+    auction_contract.unpause();
+    assert_eq!(env.auths()[0].0, pauser);
+    auction_contract.allow_broker(&Address::generate(&env));
+    assert_eq!(env.auths()[0].0, broker_manager);
+    auction_contract.set_commission_rate(&500);
+    assert_eq!(env.auths()[0].0, operator);
+    auction_contract.set_cancellation_policy(&CancellationPolicy { allow_with_bids: true, penalty_rate: 1000 });
+    assert_eq!(env.auths()[0].0, operator);
+    auction_contract.set_royalty_contract(&None);
+    assert_eq!(env.auths()[0].0, operator);
+
+    // The admin keeps the admin rights (roles and transfer).
+    auction_contract.propose_admin(&token_admin);
+    assert_eq!(env.auths()[0].0, token_admin);
+
+    // Commissions and cancellation penalties are paid to the treasury.
+    let auction_settings = AuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+        reveal_phase_time: 0,
+        kind: AuctionKind::Ascending,
+        starting_price: 0,
+        min_increment: 0,
+        relative_increment: false,
+        soft_close: SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 },
+        start_time: 0,
+        markets: vec![&env],
+    };
+    let mut auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_bid(&auction_id, &bidder, &200, &None);
+    auction_contract.cancel(&auction_id);
+    assert_eq!(market.balance(&treasury), 20);
+    assert_eq!(market.balance(&seller), 980);
+
+    auction_id = start_auction(&env, &auction_contract, &auction_settings);
+    auction_contract.place_bid(&auction_id, &bidder, &200, &None);
+    env.ledger().with_mut(|li| li.timestamp = 200);
+    auction_contract.resolve(&auction_id);
+    assert_eq!(market.balance(&treasury), 30);
+    assert_eq!(market.balance(&seller), 1170);
+    assert_eq!(token.balance(&bidder), 1);
+
+    // Revoked roles return to the admin.
+    auction_contract.revoke_role(&Role::Pauser);
+    assert_eq!(env.auths()[0].0, token_admin);
+    let revoked = env.events().all().last().unwrap().2;
+    assert_eq!(RoleUpdated::try_from_val(&env, &revoked).unwrap(), RoleUpdated {
+        version: EVENTS_VERSION,
+        role: Role::Pauser,
+        account: pauser.clone(),
+    });
+    assert_eq!(auction_contract.get_role(&Role::Pauser), token_admin);
+    auction_contract.pause();
+    assert_eq!(env.auths()[0].0, token_admin);
+}
+
+#[test]
+fn test_storage_migration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let token_admin = Address::generate(&env);
+    let seller = Address::generate(&env);
+    let bidders = [Address::generate(&env), Address::generate(&env)];
+    let (token, token_admin_client) = create_token_contract(&env, &token_admin);
+    let (market, market_admin_client) = create_token_contract(&env, &token_admin);
+    let auction_contract = create_auction_contract(&env);
+    auction_contract.initialize(&token_admin, &0, &1000, &true);
+
+    // Auctions held by legacy versions, in instance storage with their original layout.
+    let legacy_settings = LegacyAuctionSettings {
+        seller: seller.clone(),
+        token: token.address.clone(),
+        amount: 1,
+        duration: 100,
+        market: market.address.clone(),
+        reserve_price: 100,
+        ask_price: 0,
+        discount_percent: 0,
+        discount_frequency: 0,
+        compounded_discount: false,
+        sealed_phase_time: 0,
+        sealed_bid_deposit: 0,
+    };
+    let legacy_bid = |bidder: &Address, amount: i128| LegacyBidData { buyer: bidder.clone(), amount, sniper: false };
+    let (auction_id, descending_id, sealed_id, ended_id) = (1_u64, 2_u64, 3_u64, 4_u64);
+    let (sealed_amount, salt) = (120_i128, BytesN::from_array(&env, &[7_u8; 32]));
+    token_admin_client.mint(&auction_contract.address, &3);
+    market_admin_client.mint(&auction_contract.address, &360);
+    env.as_contract(&auction_contract.address, || {
+        let instance = env.storage().instance();
+        instance.set(&DataKey::AuctionData(auction_id), &LegacyAuctionData {
+            settings: legacy_settings.clone(),
+            start_time: 0,
+            bids: vec![&env, legacy_bid(&bidders[0], 150), legacy_bid(&bidders[1], 200)],
+            deposits: vec![&env],
+            id: auction_id,
+        });
+        instance.set(&AuctionRegion::Dispatcher(auction_id), &AuctionPhase::Running);
+        instance.set(&DataKey::AuctionData(descending_id), &LegacyAuctionData {
+            settings: LegacyAuctionSettings { discount_percent: 10, discount_frequency: 10, ask_price: 500, ..legacy_settings.clone() },
+            start_time: 0,
+            bids: vec![&env],
+            deposits: vec![&env],
+            id: descending_id,
+        });
+        instance.set(&AuctionRegion::Dispatcher(descending_id), &AuctionPhase::Running);
+
+        // Sealed bids were committed under their hash, without the buyer in the preimage.
+        instance.set(&DataKey::AuctionData(sealed_id), &LegacyAuctionData {
+            settings: LegacyAuctionSettings { duration: 200, sealed_phase_time: 20, sealed_bid_deposit: 10, ..legacy_settings.clone() },
+            start_time: 0,
+            bids: vec![&env],
+            deposits: vec![&env, legacy_bid(&bidders[0], 10)],
+            id: sealed_id,
+        });
+        instance.set(&AuctionRegion::Dispatcher(sealed_id), &AuctionPhase::Committing);
+        let mut sealed_data = Bytes::from_array(&env, &sealed_amount.to_be_bytes());
+        sealed_data.append(&Bytes::from_slice(&env, &salt.to_array()));
+        sealed_data.append(&Bytes::from_slice(&env, &sealed_id.to_be_bytes()));
+        instance.set(&env.crypto().sha256(&sealed_data), &0_i32);
+
+        // Ended auctions only left their phase.
+        instance.set(&AuctionRegion::Dispatcher(ended_id), &AuctionPhase::Running);
+    });
+
+    // Active auctions are converted and moved on first access, the kind derived from the settings.
+    let auction_data = auction_contract.get_auction(&auction_id).unwrap();
+    assert_eq!(auction_data.settings.kind, AuctionKind::Ascending);
+    assert_eq!(auction_data.settings.reserve_price, 100);
+    assert_eq!(auction_data.settings.soft_close, SoftClosePolicy { window: 0, extension: 0, max_extensions: 0 });
+    assert!(auction_data.settings.markets.is_empty());
+    assert_eq!(auction_data.bids.get_unchecked(1), BidData {
+        buyer: bidders[1].clone(),
+        amount: 200,
+        sniper: false,
+        quantity: 1,
+        max_amount: 0,
+        market: market.address.clone(),
+    });
+    assert_eq!(auction_data.extensions, 0);
+    assert_eq!(auction_contract.get_auction(&descending_id).unwrap().settings.kind, AuctionKind::Descending);
+    let page = auction_contract.auctions_by_seller(&seller, &None, &10);
+    assert_eq!(page.len(), 2);
+    assert_eq!(page.get_unchecked(0).id, auction_id);
+    assert_eq!(page.get_unchecked(1).id, descending_id);
+    env.as_contract(&auction_contract.address, || {
+        for id in [auction_id, descending_id] {
+            assert!(env.storage().persistent().has(&DataKey::AuctionData(id)));
+            assert!(env.storage().persistent().has(&AuctionRegion::Dispatcher(id)));
+            assert!(!env.storage().instance().has(&DataKey::AuctionData(id)));
+            assert!(!env.storage().instance().has(&AuctionRegion::Dispatcher(id)));
+        }
+    });
+
+    // Breaking storage and interface changes bump the contract version.
+    assert_eq!(auction_contract.version(), vec![&env, 0, 2, 0]);
+
+    // Ended auctions are moved by the upgrader.
+    assert_eq!(auction_contract.get_phase(&ended_id), None);
+    auction_contract.migrate(&vec![&env, auction_id, sealed_id, ended_id]);
+    assert_eq!(env.auths()[0].0, token_admin);
+    assert_eq!(auction_contract.get_phase(&ended_id), Some(AuctionPhase::Running));
+    assert_eq!(auction_contract.get_auction(&sealed_id).unwrap().settings.kind, AuctionKind::SealedBid);
+    env.as_contract(&auction_contract.address, || {
+        for id in [sealed_id, ended_id] {
+            assert!(env.storage().persistent().has(&AuctionRegion::Dispatcher(id)));
+            assert!(!env.storage().instance().has(&AuctionRegion::Dispatcher(id)));
+        }
+    });
+
+    // The migrated auctions resolve as before.
+    auction_contract.place_bid(&auction_id, &bidders[0], &0, &None);
+    assert_eq!(market.balance(&bidders[0]), 150);
+    env.ledger().with_mut(|li| li.timestamp = 101);
+    assert_eq!(auction_contract.resolve(&auction_id).unwrap().outcome, OutcomeKind::Won);
+    assert_eq!(token.balance(&bidders[1]), 1);
+    assert_eq!(market.balance(&seller), 180);
+    assert_eq!(auction_contract.resolve(&descending_id).unwrap().outcome, OutcomeKind::Ended);
+    assert_eq!(token.balance(&seller), 1);
+
+    // Legacy sealed bids are revealed with their original preimage, then settled.
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        auction_contract.place_bid(&sealed_id, &bidders[1], &sealed_amount, &Some(salt.clone()));
+    }));
+    assert!(result.is_err(), "Should panic. No deposit.");
+    auction_contract.place_bid(&sealed_id, &bidders[0], &sealed_amount, &Some(salt.clone()));
+    assert_eq!(market.balance(&bidders[0]), 150 - sealed_amount + 10);
+    env.ledger().with_mut(|li| li.timestamp = 201);
+    assert_eq!(auction_contract.resolve(&sealed_id).unwrap().outcome, OutcomeKind::Won);
+    assert_eq!(token.balance(&bidders[0]), 1);
+    assert_eq!(market.balance(&seller), 180 + 108);
+    assert_eq!(market.balance(&auction_contract.address), 0);
+    assert_eq!(auction_contract.list_auctions(&None, &10).len(), 0);
+}
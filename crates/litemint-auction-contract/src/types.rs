@@ -7,7 +7,33 @@
 */
 
 use soroban_kit::{key_constraint, soroban_tools, storage};
-use soroban_sdk::{contracttype, Address, Env, Vec};
+use soroban_sdk::{contracttype, vec, Address, Env, IntoVal, Val, Vec};
+
+use crate::auctions::behavior::ledger_times::convert_seconds_to_ledgers;
+use crate::events::Settlement;
+use crate::index;
+
+// Rates are expressed in basis points.
+pub(crate) const MAX_BPS: i128 = 10_000;
+
+// Storage version, rates were expressed in percent before version 1.
+pub(crate) const STORAGE_VERSION: u32 = 1;
+
+// Maximum anti-snipe time (in seconds).
+pub(crate) const MAX_ANTI_SNIPE_TIME: u64 = 60;
+
+// Time allowed to reveal a hidden reserve price after the end of the auction (in seconds).
+pub(crate) const RESERVE_REVEAL_TIME: u64 = 3600;
+
+// Time to live buffer after the end of an auction (in seconds), covering the reserve
+// reveal window and async resolution.
+const EXPIRATION_BUFFER: u64 = 7200;
+
+// Oracle prices are expressed with 7 decimals (e.g., 10_000_000 for parity).
+pub(crate) const PRICE_SCALE: i128 = 10_000_000;
+
+// Default maximum age of oracle prices (in seconds).
+pub(crate) const DEFAULT_MAX_PRICE_AGE: u64 = 3600;
 
 #[derive(Clone)]
 #[contracttype]
@@ -15,6 +41,46 @@ use soroban_sdk::{contracttype, Address, Env, Vec};
 pub(crate) enum DataKey {
     AdminData,
     AuctionData(u64),
+    RoyaltyContract, // Kept out of `AdminData` to preserve its stored layout.
+    StorageVersion,
+    Index(AuctionIndex),
+    IndexNode(AuctionIndex, u64),
+    CancellationPolicy,
+    SoftCloseLimits,
+    ForfeitureRate,
+    Commitment(u64, Address),
+    HiddenReserve(u64),
+    BrokerWhitelist(Address),
+    MaxPriceAge,
+    AuctionOutcome(u64),
+    PendingAdmin,
+    Role(Role),
+}
+
+// Indexes of active auctions.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum AuctionIndex {
+    Active,
+    Seller(Address),
+    Token(Address),
+    Market(Address),
+}
+
+// First and last auctions of a non-empty index.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct IndexBounds {
+    pub head: u64,
+    pub tail: u64,
+}
+
+// Entry of an auction in an index, linked to its neighbors.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct IndexNode {
+    pub prev: Option<u64>,
+    pub next: Option<u64>,
 }
 
 #[contracttype]
@@ -22,13 +88,18 @@ pub(crate) enum DataKey {
 pub(crate) enum AuctionRegion {
     Dispatcher(u64),
     Resolve(u64),
+    Circuit,
 }
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
-pub(crate) enum AuctionPhase {
+pub enum AuctionPhase {
+    Scheduled,
     Committing,
+    Revealing,
     Running,
+    Settled,
+    Cancelled,
 }
 
 #[contracttype]
@@ -41,12 +112,141 @@ pub(crate) struct AdminData {
     pub extendable_auctions: bool,
 }
 
+impl AdminData {
+    // Load the admin data.
+    // Legacy records (commission rate in percent) are migrated to basis points.
+    pub fn load(env: &Env) -> Self {
+        let mut admin_data = storage::get::<DataKey, AdminData>(env, &DataKey::AdminData).unwrap();
+        if env
+            .storage()
+            .instance()
+            .get::<DataKey, u32>(&DataKey::StorageVersion)
+            .unwrap_or(0)
+            < STORAGE_VERSION
+        {
+            admin_data.commission_rate = admin_data.commission_rate.checked_mul(100).unwrap();
+            admin_data.store(env);
+        }
+        admin_data
+    }
+
+    // Store the admin data, stamped with the current storage version.
+    pub fn store(&self, env: &Env) {
+        storage::set::<DataKey, AdminData>(env, &DataKey::AdminData, self);
+        env.storage()
+            .instance()
+            .set::<DataKey, u32>(&DataKey::StorageVersion, &STORAGE_VERSION);
+    }
+}
+
+// Privileged roles, held by the admin unless granted to another account.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Upgrader,       // Contract upgrades.
+    ConfigOperator, // Auction settings and policies.
+    Treasury,       // Recipient of commissions, penalties and forfeitures.
+    BrokerManager,  // Oracle broker whitelist.
+    Pauser,         // Circuit breaker.
+}
+
+impl Role {
+    // Account holding the role, the admin if not granted.
+    pub fn holder(&self, env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::Role(*self))
+            .unwrap_or_else(|| AdminData::load(env).admin)
+    }
+
+    // Require authorization from the role holder.
+    pub fn require_auth(&self, env: &Env) {
+        self.holder(env).require_auth();
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CancellationPolicy {
+    pub allow_with_bids: bool,
+    pub penalty_rate: i128, // Basis points of the highest bid.
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct BidData {
     pub buyer: Address,
     pub amount: i128,
     pub sniper: bool,
+    pub quantity: i128,
+    pub max_amount: i128, // Hidden maximum for proxy bids, zero otherwise.
+    pub market: Address,  // Currency of the bid.
+}
+
+impl BidData {
+    // Amount held in escrow for this bid.
+    pub fn escrow(&self) -> i128 {
+        self.amount.max(self.max_amount)
+    }
+
+    // Bid amount normalized to the auction `market`, at the last known price.
+    pub fn value(&self, env: &Env, settings: &AuctionSettings) -> i128 {
+        settings.normalize_last(env, &self.market, self.amount)
+    }
+}
+
+// Soft-close (anti-snipe) policy.
+// Bids placed within `window` of the end of the auction extend it by `extension`,
+// up to `max_extensions` times.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SoftClosePolicy {
+    pub window: u64,
+    pub extension: u64,
+    pub max_extensions: u32,
+}
+
+impl SoftClosePolicy {
+    // Load the admin limits.
+    // Default to the admin `anti_snipe_time` for both window and extension, with unlimited extensions.
+    pub fn load_limits(env: &Env) -> Self {
+        env.storage()
+            .instance()
+            .get::<DataKey, SoftClosePolicy>(&DataKey::SoftCloseLimits)
+            .unwrap_or_else(|| {
+                let anti_snipe_time = AdminData::load(env).anti_snipe_time;
+                SoftClosePolicy {
+                    window: anti_snipe_time,
+                    extension: anti_snipe_time,
+                    max_extensions: u32::MAX,
+                }
+            })
+    }
+
+    // Resolve the policy applying to an auction, the admin default if unset.
+    pub fn resolve(&self, env: &Env) -> Self {
+        match self.window {
+            0 => {
+                let anti_snipe_time = AdminData::load(env).anti_snipe_time;
+                SoftClosePolicy {
+                    window: anti_snipe_time,
+                    extension: anti_snipe_time,
+                    max_extensions: Self::load_limits(env).max_extensions,
+                }
+            }
+            _ => self.clone(),
+        }
+    }
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum AuctionKind {
+    Ascending,
+    Descending,
+    SealedBid,
+    SecondPrice,
+    MultiUnit,
 }
 
 #[contracttype]
@@ -64,10 +264,71 @@ pub struct AuctionSettings {
     pub compounded_discount: bool,
     pub sealed_phase_time: u64,
     pub sealed_bid_deposit: i128,
+    pub reveal_phase_time: u64,
+    pub kind: AuctionKind,
+    pub starting_price: i128,
+    pub min_increment: i128,
+    pub relative_increment: bool,
+    pub soft_close: SoftClosePolicy, // Zero `window` for the admin default.
+    pub start_time: u64,             // Scheduled start time, zero to start immediately.
+    pub markets: Vec<Address>,       // Additional currencies accepted for bids.
+}
+
+// Value of `amount` at the price of `data` (see `PRICE_SCALE`).
+fn convert_price(amount: i128, data: &MarketData) -> i128 {
+    amount
+        .checked_mul(data.price)
+        .and_then(|val| val.checked_div(PRICE_SCALE))
+        .unwrap()
+}
+
+impl AuctionSettings {
+    // Check that bids are accepted in `market`.
+    pub fn accepts(&self, market: &Address) -> bool {
+        *market == self.market || self.markets.contains(market)
+    }
+
+    // Value of `amount` in `market`, normalized to the auction `market`
+    // using the last oracle price feed, which must not be older than the maximum price age.
+    pub fn normalize(&self, env: &Env, market: &Address, amount: i128) -> i128 {
+        if *market == self.market {
+            return amount;
+        }
+        let data = self.price_feed(env, market);
+        let max_age = env
+            .storage()
+            .instance()
+            .get::<DataKey, u64>(&DataKey::MaxPriceAge)
+            .unwrap_or(DEFAULT_MAX_PRICE_AGE);
+        assert!(
+            env.ledger().timestamp() <= data.timestamp.saturating_add(max_age),
+            "Stale price feed"
+        );
+        convert_price(amount, &data)
+    }
+
+    // Value of `amount` in `market`, normalized to the auction `market`
+    // using the last oracle price feed regardless of its age, so that bids already placed
+    // can be ranked and settled when the feed stops.
+    pub fn normalize_last(&self, env: &Env, market: &Address, amount: i128) -> i128 {
+        if *market == self.market {
+            return amount;
+        }
+        convert_price(amount, &self.price_feed(env, market))
+    }
+
+    fn price_feed(&self, env: &Env, market: &Address) -> MarketData {
+        assert!(self.accepts(market));
+        let data =
+            storage::get::<MarketDataKey, MarketData>(env, &MarketDataKey::Index(market.clone()))
+                .expect("No price feed");
+        assert_eq!(data.asset, self.market);
+        data
+    }
 }
 
 #[contracttype]
-#[storage(Instance, DataKeyConstraint)]
+#[storage(Persistent, DataKeyConstraint)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct AuctionData {
     pub settings: AuctionSettings,
@@ -75,9 +336,100 @@ pub struct AuctionData {
     pub bids: Vec<BidData>,
     pub deposits: Vec<BidData>,
     pub id: u64,
+    pub extensions: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum OutcomeKind {
+    Won,
+    Ended, // No winner.
+    Cancelled,
+}
+
+// Archived outcome of an auction, kept after settlement or cancellation.
+// `winners` carry the price paid and fees, `end_time` is the scheduled end of the auction
+// (including extensions) and `settled_time` the time of settlement or cancellation.
+#[contracttype]
+#[storage(Persistent, DataKeyConstraint)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AuctionOutcome {
+    pub id: u64,
+    pub outcome: OutcomeKind,
+    pub kind: AuctionKind,
+    pub seller: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub market: Address,
+    pub reserve_price: i128,
+    pub winners: Vec<Settlement>,
+    pub clearing_price: i128,
+    pub end_time: u64,
+    pub settled_time: u64,
 }
 
 impl AuctionData {
+    // End of the reveal window for sealed bid auctions.
+    pub fn reveal_deadline(&self) -> u64 {
+        self.start_time + self.settings.sealed_phase_time + self.settings.reveal_phase_time
+    }
+
+    // Load an auction.
+    // Legacy auctions held in instance storage (see `LegacyAuctionData`) are converted and moved
+    // to persistent storage, along with their phase, hidden reserve and sealed bids, and added
+    // to the indexes. Legacy sealed bid commitments remain keyed by hash (see `reveal_bid`).
+    pub fn load(env: &Env, id: u64) -> Option<Self> {
+        let key = DataKey::AuctionData(id);
+        if let Some(auction_data) = storage::get::<DataKey, AuctionData>(env, &key) {
+            return Some(auction_data);
+        }
+        let auction_data = env
+            .storage()
+            .instance()
+            .get::<DataKey, LegacyAuctionData>(&key)?
+            .into_auction_data(env);
+        env.storage().instance().remove::<DataKey>(&key);
+        migrate_entry(env, &AuctionRegion::Dispatcher(id));
+        migrate_entry(env, &DataKey::HiddenReserve(id));
+        for bid in auction_data.deposits.iter() {
+            migrate_entry(env, &DataKey::Commitment(id, bid.buyer));
+        }
+        index::add(env, &auction_data);
+        auction_data.store(env);
+        Some(auction_data)
+    }
+
+    // Store the auction, extending its time to live.
+    pub fn store(&self, env: &Env) {
+        storage::set::<DataKey, AuctionData>(env, &DataKey::AuctionData(self.id), self);
+        self.extend_ttl(env);
+    }
+
+    // Extend the time to live of the auction entries (data, phase, hidden reserve, sealed bids
+    // and index entries) until the end of the auction (or reveal window), plus `EXPIRATION_BUFFER`.
+    pub fn extend_ttl(&self, env: &Env) {
+        let end_time = (self.start_time + self.settings.duration).max(self.reveal_deadline());
+        let ledgers = convert_seconds_to_ledgers(
+            (end_time + EXPIRATION_BUFFER).saturating_sub(env.ledger().timestamp()),
+        );
+        let mut keys: Vec<Val> = vec![
+            env,
+            DataKey::AuctionData(self.id).into_val(env),
+            DataKey::HiddenReserve(self.id).into_val(env),
+            AuctionRegion::Dispatcher(self.id).into_val(env),
+        ];
+        for bid in self.deposits.iter() {
+            keys.push_back(DataKey::Commitment(self.id, bid.buyer).into_val(env));
+        }
+        let persistent = env.storage().persistent();
+        for key in keys.iter() {
+            if persistent.has(&key) {
+                persistent.extend_ttl(&key, ledgers, ledgers);
+            }
+        }
+        index::extend_ttl(env, self, ledgers);
+    }
+
     pub fn new(
         settings: AuctionSettings,
         start_time: u64,
@@ -91,6 +443,137 @@ impl AuctionData {
             bids,
             deposits,
             id,
+            extensions: 0,
         }
     }
 }
+
+// Layout of `AuctionSettings` before storage version 1.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct LegacyAuctionSettings {
+    pub seller: Address,
+    pub token: Address,
+    pub amount: i128,
+    pub duration: u64,
+    pub market: Address,
+    pub reserve_price: i128,
+    pub ask_price: i128,
+    pub discount_percent: u32,
+    pub discount_frequency: u64,
+    pub compounded_discount: bool,
+    pub sealed_phase_time: u64,
+    pub sealed_bid_deposit: i128,
+}
+
+// Layout of `BidData` before storage version 1.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct LegacyBidData {
+    pub buyer: Address,
+    pub amount: i128,
+    pub sniper: bool,
+}
+
+// Layout of `AuctionData` before storage version 1, held in instance storage.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct LegacyAuctionData {
+    pub settings: LegacyAuctionSettings,
+    pub start_time: u64,
+    pub bids: Vec<LegacyBidData>,
+    pub deposits: Vec<LegacyBidData>,
+    pub id: u64,
+}
+
+impl LegacyAuctionData {
+    // Convert to the current layout.
+    // The kind is derived from the settings as legacy versions did: discounted auctions are
+    // descending, auctions with both a sealed phase and a deposit are sealed bid auctions
+    // (revealed until the end), others are ascending. Other settings keep their defaults.
+    pub fn into_auction_data(self, env: &Env) -> AuctionData {
+        let legacy = self.settings;
+        let kind = if legacy.discount_percent > 0 && legacy.discount_frequency > 0 {
+            AuctionKind::Descending
+        } else if legacy.sealed_bid_deposit > 0 && legacy.sealed_phase_time > 0 {
+            AuctionKind::SealedBid
+        } else {
+            AuctionKind::Ascending
+        };
+        let reveal_phase_time = match kind {
+            AuctionKind::SealedBid => legacy.duration.saturating_sub(legacy.sealed_phase_time),
+            _ => 0,
+        };
+        let settings = AuctionSettings {
+            seller: legacy.seller,
+            token: legacy.token,
+            amount: legacy.amount,
+            duration: legacy.duration,
+            market: legacy.market.clone(),
+            reserve_price: legacy.reserve_price,
+            ask_price: legacy.ask_price,
+            discount_percent: legacy.discount_percent,
+            discount_frequency: legacy.discount_frequency,
+            compounded_discount: legacy.compounded_discount,
+            sealed_phase_time: legacy.sealed_phase_time,
+            sealed_bid_deposit: legacy.sealed_bid_deposit,
+            reveal_phase_time,
+            kind,
+            starting_price: 0,
+            min_increment: 0,
+            relative_increment: false,
+            soft_close: SoftClosePolicy {
+                window: 0,
+                extension: 0,
+                max_extensions: 0,
+            },
+            start_time: 0,
+            markets: Vec::new(env),
+        };
+
+        // Bids are for the whole lot, deposits for none, in the auction `market`.
+        let convert = |bids: Vec<LegacyBidData>, quantity: i128| {
+            let mut converted = Vec::new(env);
+            for bid in bids.iter() {
+                converted.push_back(BidData {
+                    buyer: bid.buyer,
+                    amount: bid.amount,
+                    sniper: bid.sniper,
+                    quantity,
+                    max_amount: 0,
+                    market: legacy.market.clone(),
+                });
+            }
+            converted
+        };
+        let bids = convert(self.bids, settings.amount);
+        let deposits = convert(self.deposits, 0);
+        AuctionData::new(settings, self.start_time, bids, deposits, self.id)
+    }
+}
+
+// Move an entry from instance to persistent storage, if any.
+pub(crate) fn migrate_entry<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    if let Some(value) = env.storage().instance().get::<K, Val>(key) {
+        env.storage().persistent().set::<K, Val>(key, &value);
+        env.storage().instance().remove::<K>(key);
+    }
+}
+
+#[contracttype]
+#[key_constraint(MarketDataKeyConstraint)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum MarketDataKey {
+    Index(Address),
+}
+
+// Oracle price feed for a market (topic), i.e., price of one unit in `asset`,
+// published at `timestamp` (in seconds).
+#[contracttype]
+#[storage(Instance, MarketDataKeyConstraint)]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MarketData {
+    pub price: i128,
+    pub asset: Address,
+    pub timestamp: u64,
+}
@@ -6,29 +6,26 @@
     MIT License
 */
 
+use super::r#impl::calculate_share;
 use crate::types::{License, MarketData, MarketDataKey};
 use soroban_kit::storage;
 use soroban_sdk::{vec, Address, Env, Vec};
 
 pub struct CompensationPercentage;
 
-// Percentage royalty payment.
+// Percentage royalty payment (basis points of the market price, rounded up).
 impl super::r#impl::Agreement for CompensationPercentage {
     fn calculate_interest(&self, env: &Env, license: &License) -> Vec<(i128, Address)> {
         // Fed to contract from oracle broker.
         let data = storage::get::<MarketDataKey, MarketData>(
-            &env,
+            env,
             &MarketDataKey::Index(license.terms.property.clone()),
         )
         .unwrap();
         let mut interest = vec![
             env,
             (
-                data.price
-                    .checked_mul(license.terms.royalty_interest)
-                    .and_then(|val| val.checked_add(99))
-                    .and_then(|val| val.checked_div(100))
-                    .unwrap(),
+                calculate_share(data.price, license.terms.royalty_interest),
                 data.asset,
             ),
         ];
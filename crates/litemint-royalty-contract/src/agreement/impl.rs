@@ -6,15 +6,25 @@
     MIT License
 */
 
-use crate::types::{AdminData, AdminDataKey};
+use crate::types::{AdminData, DataKey, Role, MAX_BPS};
 use crate::types::{Compensation, License, LicenseStatus};
-use soroban_kit::storage;
-use soroban_sdk::{token, Address, Env, Vec};
+use soroban_sdk::{token, vec, Address, Env, Vec};
 
 use super::compensation_fixed::*;
 use super::compensation_percentage::*;
 use super::compensation_subscription::*;
 
+pub const MAX_RECIPIENTS: u32 = 10;
+
+// Share of `amount` at `rate` (basis points), rounded up.
+pub fn calculate_share(amount: i128, rate: i128) -> i128 {
+    amount
+        .checked_mul(rate)
+        .and_then(|val| val.checked_add(MAX_BPS - 1))
+        .and_then(|val| val.checked_div(MAX_BPS))
+        .unwrap()
+}
+
 pub trait Agreement {
     fn execute(&self, env: &Env, license: &mut License) {
         #[cfg(not(test))]
@@ -47,11 +57,21 @@ pub trait Agreement {
             };
 
         let licensor_balance =
-            token::Client::new(&env, &license.terms.property).balance(&license.terms.licensor);
+            token::Client::new(env, &license.terms.property).balance(&license.terms.licensor);
+
+        // Enforcement and grace period are suspended while the property is frozen.
+        let frozen_time = env
+            .storage()
+            .persistent()
+            .get::<DataKey, u64>(&DataKey::Frozen(license.terms.property.clone()));
+
+        // The grace period is also suspended while the contract is paused.
+        license.shift_grace_time(env, frozen_time.is_some());
 
         match license.status {
             LicenseStatus::Paid => {
                 let now = env.ledger().timestamp();
+                let grace_start = frozen_time.unwrap_or(now);
                 let property = token::Client::new(env, &license.terms.property);
                 // Licensor holding.
                 if licensor_balance > 0 {
@@ -60,7 +80,7 @@ pub trait Agreement {
                 // Ownership has changed, payment due.
                 else if property.balance(&license.licensee) == 0 {
                     license.status = LicenseStatus::Unpaid;
-                    license.grace_time = now + license.terms.grace_period;
+                    license.grace_time = grace_start + license.terms.grace_period;
                     license.transferring = true;
                 // Recurring period elapsed, payment due.
                 } else if has_recur_elapsed(env, license, now, licensor_balance) {
@@ -69,14 +89,18 @@ pub trait Agreement {
                         true => license.recur_time = now + license.terms.recur_period,
                         false => license.recur_time += license.terms.recur_period,
                     }
-                    license.grace_time = (now + license.terms.grace_period).min(license.recur_time);
+                    license.grace_time =
+                        (grace_start + license.terms.grace_period).min(license.recur_time);
                 }
             }
-            LicenseStatus::Unpaid if require_enforcement(env, license, licensor_balance) => {
+            LicenseStatus::Unpaid
+                if frozen_time.is_none() && require_enforcement(env, license, licensor_balance) =>
+            {
                 // Successful interest calculation is required to guarantee symmetry with payments.
-                self.calculate_interest(&env, &license);
+                self.calculate_interest(env, license);
 
-                // Send the lien to licensor so they can seize the property.
+                // Send the lien to licensor so they can seize the property
+                // (on behalf of all recipients).
                 token::Client::new(env, &license.terms.lien).transfer(
                     &env.current_contract_address(),
                     &license.terms.licensor,
@@ -88,38 +112,31 @@ pub trait Agreement {
         }
     }
 
-    fn pay(&self, env: &Env, new_licensee: &Address, license: &mut License) {
+    fn pay(&self, env: &Env, payer: &Address, new_licensee: &Address, license: &mut License) {
         self.execute(env, license);
 
         if license.status == LicenseStatus::Unpaid {
             let interest = self.calculate_interest(env, license);
             for (amount, market) in interest {
                 let payment_token = token::Client::new(env, &market);
-                let admin_data =
-                    storage::get::<AdminDataKey, AdminData>(env, &AdminDataKey::Root).unwrap();
-                let (admin_share, licensor_share) = if admin_data.commission_rate > 0 {
-                    let admin_share = amount
-                        .checked_mul(admin_data.commission_rate)
-                        .and_then(|val| val.checked_add(99))
-                        .and_then(|val| val.checked_div(100))
-                        .unwrap()
-                        .max(1);
-
-                    let licensor_share = (amount - admin_share).max(1);
-                    (admin_share, licensor_share)
-                } else {
-                    // No commission, all goes to licensor.
-                    (0, amount)
-                };
+                // Commission is rounded up, the licensor share is the exact remainder.
+                let admin_share = calculate_share(amount, AdminData::load(env).commission_rate);
+                let licensor_share = amount - admin_share;
 
+                // Collect the payment, the admin share goes to the treasury.
+                payment_token.transfer(payer, &env.current_contract_address(), &amount);
                 if admin_share > 0 {
                     payment_token.transfer(
-                        new_licensee,
                         &env.current_contract_address(),
+                        &Role::Treasury.holder(env),
                         &admin_share,
                     );
                 }
-                payment_token.transfer(new_licensee, &license.terms.licensor, &licensor_share);
+                for (recipient, share) in split_shares(env, license, licensor_share) {
+                    if share > 0 {
+                        payment_token.transfer(&env.current_contract_address(), &recipient, &share);
+                    }
+                }
             }
 
             license.transferring = false;
@@ -130,15 +147,56 @@ pub trait Agreement {
         self.execute(env, license);
     }
 
+    // Interest due by `new_licensee` for the next payment, without enforcement.
+    fn quote(&self, env: &Env, new_licensee: &Address, license: &License) -> Vec<(i128, Address)> {
+        let mut license = license.clone();
+        match license.status {
+            LicenseStatus::Paid if license.licensee != *new_licensee => {
+                license.transferring = true;
+                self.calculate_interest(env, &license)
+            }
+            LicenseStatus::Unpaid => self.calculate_interest(env, &license),
+            _ => Vec::new(env),
+        }
+    }
+
     fn calculate_interest(&self, env: &Env, license: &License) -> Vec<(i128, Address)>;
 }
 
+// Split the licensor share among the recipients in proportion to their basis points.
+// Shares are rounded down and the remainder goes to the first recipient,
+// so that the shares always add up to `amount`.
+// Without recipients, the licensor receives the entire share.
+pub fn split_shares(env: &Env, license: &License, amount: i128) -> Vec<(Address, i128)> {
+    let recipients = &license.terms.recipients;
+    if recipients.is_empty() {
+        return vec![env, (license.terms.licensor.clone(), amount)];
+    }
+    let mut shares: Vec<(Address, i128)> = Vec::new(env);
+    let mut remainder = amount;
+    for recipient in recipients.iter() {
+        let share = amount
+            .checked_mul(recipient.share as i128)
+            .and_then(|val| val.checked_div(MAX_BPS))
+            .unwrap();
+        remainder -= share;
+        shares.push_back((recipient.address, share));
+    }
+    let (address, share) = shares.get_unchecked(0);
+    shares.set(0, (address, share + remainder));
+    shares
+}
+
 impl Agreement for Compensation {
-    fn pay(&self, env: &Env, new_licensee: &Address, license: &mut License) {
+    fn pay(&self, env: &Env, payer: &Address, new_licensee: &Address, license: &mut License) {
         match self {
-            Compensation::Fixed => CompensationFixed.pay(env, new_licensee, license),
-            Compensation::Percentage => CompensationPercentage.pay(env, new_licensee, license),
-            Compensation::Subscription => CompensationSubscription.pay(env, new_licensee, license),
+            Compensation::Fixed => CompensationFixed.pay(env, payer, new_licensee, license),
+            Compensation::Percentage => {
+                CompensationPercentage.pay(env, payer, new_licensee, license)
+            }
+            Compensation::Subscription => {
+                CompensationSubscription.pay(env, payer, new_licensee, license)
+            }
         }
     }
 
@@ -0,0 +1,38 @@
+/*
+    Date: 2023
+    Author: Fred Kyung-jin Rezeau <fred@litemint.com>
+    Copyright (c) 2023 Litemint LLC
+
+    MIT License
+*/
+
+//! Contract events.
+//! Event data are versioned `contracttype` structs, matching the auction contract events.
+
+use soroban_sdk::{contracttype, symbol_short, Address, Symbol};
+
+use crate::types::Role;
+
+// Version of the event data, incremented on breaking changes.
+pub const EVENTS_VERSION: u32 = 1;
+
+// Event topics.
+pub const ADMIN: Symbol = symbol_short!("ADMIN");
+
+// (ADMIN, "rate")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CommissionRateUpdated {
+    pub version: u32,
+    pub old_rate: i128,
+    pub new_rate: i128,
+}
+
+// (ADMIN, "granted") and (ADMIN, "revoked")
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RoleUpdated {
+    pub version: u32,
+    pub role: Role,
+    pub account: Address,
+}
@@ -9,15 +9,25 @@
 #![no_std]
 
 mod agreement; // Agreement model and mechanisms.
-mod types;
+pub mod events;
+pub mod types;
 
-use soroban_kit::{oracle, oracle_subscriber, storage};
-use soroban_sdk::{contract, contractimpl, contractmeta, token, Address, Env};
+use soroban_kit::{
+    oracle, oracle_subscriber, soroban_tools, storage, when_closed, when_opened, CircuitBreaker,
+    TransitionHandler,
+};
+use soroban_sdk::{
+    contract, contractimpl, contractmeta, symbol_short, token, Address, BytesN, Env, Vec,
+};
 use types::{MarketData, MarketDataKey};
 
 use crate::{
-    agreement::Agreement,
-    types::{AdminData, AdminDataKey, Compensation, DataKey, License, LicenseStatus, Terms},
+    agreement::{Agreement, MAX_RECIPIENTS},
+    events::{CommissionRateUpdated, RoleUpdated, ADMIN, EVENTS_VERSION},
+    types::{
+        extend_instance_ttl, paused_duration, AdminData, AdminDataKey, Compensation, DataKey,
+        License, LicenseStatus, Role, Terms, MAX_BPS,
+    },
 };
 
 contractmeta!(
@@ -34,24 +44,104 @@ pub trait RoyaltyInterface {
     // Licensee authorization required.
     fn pay(env: Env, property: Address, licensee: Address) -> License;
 
+    // Pay the royalty for a property on behalf of the licensee (e.g., marketplace settlement).
+    // Payer authorization required.
+    fn pay_for(env: Env, property: Address, licensee: Address, payer: Address) -> License;
+
+    // Retrieve the royalty due by `licensee` for a property (e.g., after transfer of ownership).
+    // Returns a list of `(amount, currency)`, empty if nothing is due or the property is not registered.
+    // No authorization required.
+    fn quote(env: Env, property: Address, licensee: Address) -> Vec<(i128, Address)>;
+
+    // Retrieve the license for a property.
+    // No authorization required.
+    fn get_license(env: Env, property: Address) -> Option<License>;
+
     // Add a property to the contract.
+    // Percentage `royalty_interest` is expressed in basis points (e.g., 750 for 7.5%).
+    // Royalties are split among `recipients` by basis points (must total 10000),
+    // or paid entirely to the licensor if empty. The licensor receives the lien on breach.
     // Licensor authorization required.
     fn add_property(env: Env, terms: Terms);
 }
 
 pub trait Subscriber {
     // Allow an oracle broker to publish price feeds to this contract.
-    // Admin authorization required.
+    // Broker manager authorization required.
     fn allow_broker(env: Env, broker: Address);
 
     // Deny an oracle broker.
-    // Admin authorization required.
+    // Broker manager authorization required.
     fn deny_broker(env: Env, broker: Address);
 }
 
+pub trait Pausable {
+    // Pause the contract (circuit breaker).
+    // Blocks `execute`, `pay`, `pay_for` and `add_property` until unpaused.
+    // Grace periods are extended by the time spent paused.
+    // Pauser authorization required.
+    fn pause(env: Env);
+
+    // Unpause the contract.
+    // Pauser authorization required.
+    fn unpause(env: Env);
+
+    // Freeze a property under investigation (e.g., disputed sale).
+    // The license cannot be breached and its grace period is suspended while frozen.
+    // Pauser authorization required.
+    fn freeze(env: Env, property: Address);
+
+    // Unfreeze a property, resuming the grace period where it was suspended.
+    // Pauser authorization required.
+    fn unfreeze(env: Env, property: Address) -> License;
+}
+
+pub trait Administrable {
+    // Grant `role` to `account`, replacing the current holder.
+    // Roles not granted are held by the admin.
+    // Admin authorization required.
+    fn grant_role(env: Env, role: Role, account: Address);
+
+    // Revoke `role`, returning it to its default holder.
+    // Admin authorization required.
+    fn revoke_role(env: Env, role: Role);
+
+    // Retrieve the account holding `role`.
+    // No authorization required.
+    fn get_role(env: Env, role: Role) -> Address;
+
+    // Set the marketplace commission rate, in basis points (e.g., 250 for 2.5%).
+    // Config operator authorization required.
+    fn set_commission_rate(env: Env, commission_rate: i128);
+
+    // Upgrade this contract.
+    // Upgrader authorization required.
+    fn upgrade(env: Env, wasm_hash: BytesN<32>);
+
+    // Move legacy licenses (held in instance storage) to persistent storage.
+    // Licenses are also moved when first accessed.
+    // Upgrader authorization required.
+    fn migrate(env: Env, properties: Vec<Address>);
+}
+
 #[contract]
 #[oracle_subscriber(Address, MarketData)]
-struct RoyaltyContract;
+pub struct RoyaltyContract;
+
+// Circuit breaker for the pausable contract operations.
+#[derive(CircuitBreaker)]
+struct RoyaltyCircuit;
+
+impl RoyaltyCircuit {
+    #[when_closed]
+    fn require_closed(&self, env: &Env) {}
+
+    #[when_closed(trigger = true)]
+    fn open(&self, env: &Env) {}
+
+    #[when_opened(trigger = true)]
+    fn close(&self, env: &Env) {}
+}
 
 // Implement the Oracle events.
 impl oracle::Events<Address, MarketData> for RoyaltyContract {
@@ -63,7 +153,7 @@ impl oracle::Events<Address, MarketData> for RoyaltyContract {
     fn on_sync_receive(env: &Env, topic: &Address, envelope: &oracle::Envelope, data: &MarketData) {
         require_broker_whitelisted(env, &envelope.broker);
         storage::set::<MarketDataKey, MarketData>(
-            &env,
+            env,
             &MarketDataKey::Index(topic.clone()),
             reconcile_data(&mut data.clone()),
         );
@@ -79,14 +169,14 @@ impl oracle::Events<Address, MarketData> for RoyaltyContract {
         // Make sure this cross-contract call is from broker.
         envelope.broker.require_auth();
         storage::set::<MarketDataKey, MarketData>(
-            &env,
+            env,
             &MarketDataKey::Index(topic.clone()),
             reconcile_data(&mut data.clone()),
         );
     }
 }
 
-fn reconcile_data<'a>(data: &'a mut MarketData) -> &'a mut MarketData {
+fn reconcile_data(data: &mut MarketData) -> &mut MarketData {
     // We might implement more sophisticated data reconciliation strategies to derive
     // additional compensation models (e.g., average...).
 
@@ -98,60 +188,192 @@ fn reconcile_data<'a>(data: &'a mut MarketData) -> &'a mut MarketData {
 #[contractimpl]
 impl Subscriber for RoyaltyContract {
     fn allow_broker(env: Env, broker: Address) {
-        storage::get::<AdminDataKey, AdminData>(&env, &AdminDataKey::Root)
-            .unwrap()
-            .admin
-            .require_auth();
+        Role::BrokerManager.require_auth(&env);
         update_broker_whitelist(&env, &broker, false);
     }
 
     fn deny_broker(env: Env, broker: Address) {
-        storage::get::<AdminDataKey, AdminData>(&env, &AdminDataKey::Root)
-            .unwrap()
-            .admin
-            .require_auth();
+        Role::BrokerManager.require_auth(&env);
         update_broker_whitelist(&env, &broker, true);
     }
 }
 
+#[contractimpl]
+impl Pausable for RoyaltyContract {
+    fn pause(env: Env) {
+        Role::Pauser.require_auth(&env);
+        RoyaltyCircuit.open(&env);
+        env.storage()
+            .instance()
+            .set::<DataKey, u64>(&DataKey::PausedTime, &env.ledger().timestamp());
+    }
+
+    fn unpause(env: Env) {
+        Role::Pauser.require_auth(&env);
+        RoyaltyCircuit.close(&env);
+        let paused_duration = paused_duration(&env);
+        env.storage()
+            .instance()
+            .set::<DataKey, u64>(&DataKey::PausedDuration, &paused_duration);
+        env.storage()
+            .instance()
+            .remove::<DataKey>(&DataKey::PausedTime);
+    }
+
+    fn freeze(env: Env, property: Address) {
+        Role::Pauser.require_auth(&env);
+        let mut license = License::load(&env, &property).unwrap();
+        assert!(!env
+            .storage()
+            .persistent()
+            .has::<DataKey>(&DataKey::Frozen(property.clone())));
+        env.storage().persistent().set::<DataKey, u64>(
+            &DataKey::Frozen(property.clone()),
+            &env.ledger().timestamp(),
+        );
+        // Apply pauses prior to the freeze, later ones are covered by `unfreeze`.
+        license.shift_grace_time(&env, false);
+        license.store(&env);
+    }
+
+    fn unfreeze(env: Env, property: Address) -> License {
+        Role::Pauser.require_auth(&env);
+        let mut license = License::load(&env, &property).unwrap();
+        let frozen_time = env
+            .storage()
+            .persistent()
+            .get::<DataKey, u64>(&DataKey::Frozen(property.clone()))
+            .unwrap();
+        env.storage()
+            .persistent()
+            .remove::<DataKey>(&DataKey::Frozen(property.clone()));
+
+        // Resume the grace period where it was suspended.
+        if license.status == LicenseStatus::Unpaid {
+            license.grace_time += env.ledger().timestamp() - frozen_time;
+        }
+        license.shift_grace_time(&env, true);
+        license.store(&env);
+        license
+    }
+}
+
+#[contractimpl]
+impl Administrable for RoyaltyContract {
+    fn grant_role(env: Env, role: Role, account: Address) {
+        AdminData::load(&env).admin.require_auth();
+        env.storage()
+            .instance()
+            .set::<DataKey, Address>(&DataKey::Role(role), &account);
+        env.events().publish(
+            (ADMIN, symbol_short!("granted")),
+            RoleUpdated {
+                version: EVENTS_VERSION,
+                role,
+                account,
+            },
+        );
+    }
+
+    fn revoke_role(env: Env, role: Role) {
+        AdminData::load(&env).admin.require_auth();
+        let account = role.holder(&env);
+        env.storage()
+            .instance()
+            .remove::<DataKey>(&DataKey::Role(role));
+        env.events().publish(
+            (ADMIN, symbol_short!("revoked")),
+            RoleUpdated {
+                version: EVENTS_VERSION,
+                role,
+                account,
+            },
+        );
+    }
+
+    fn get_role(env: Env, role: Role) -> Address {
+        role.holder(&env)
+    }
+
+    fn set_commission_rate(env: Env, commission_rate: i128) {
+        Role::ConfigOperator.require_auth(&env);
+        assert!((0..=MAX_BPS).contains(&commission_rate));
+        let mut admin_data = AdminData::load(&env);
+        env.events().publish(
+            (ADMIN, symbol_short!("rate")),
+            CommissionRateUpdated {
+                version: EVENTS_VERSION,
+                old_rate: admin_data.commission_rate,
+                new_rate: commission_rate,
+            },
+        );
+        admin_data.commission_rate = commission_rate;
+        admin_data.store(&env);
+    }
+
+    fn upgrade(env: Env, wasm_hash: BytesN<32>) {
+        Role::Upgrader.require_auth(&env);
+        env.deployer().update_current_contract_wasm(wasm_hash);
+    }
+
+    fn migrate(env: Env, properties: Vec<Address>) {
+        Role::Upgrader.require_auth(&env);
+        for property in properties.iter() {
+            License::load(&env, &property);
+        }
+    }
+}
+
 #[contractimpl]
 impl RoyaltyInterface for RoyaltyContract {
     fn execute(env: Env, property: Address) -> License {
-        let mut license =
-            storage::get::<DataKey, License>(&env, &DataKey::License(property.clone())).unwrap();
+        RoyaltyCircuit.require_closed(&env);
+
+        let mut license = License::load(&env, &property).unwrap();
         agreement!(license.terms.compensation).execute(&env, &mut license);
-        storage::set::<DataKey, License>(&env, &DataKey::License(property), &license);
+        license.store(&env);
         license
     }
 
     fn pay(env: Env, property: Address, licensee: Address) -> License {
         licensee.require_auth();
+        pay_license(&env, property, &licensee, &licensee)
+    }
 
-        let mut license =
-            storage::get::<DataKey, License>(&env, &DataKey::License(property.clone())).unwrap();
-        assert_eq!(
-            token::Client::new(&env, &license.terms.property).balance(&licensee),
-            1
-        );
-        assert_eq!(
-            token::Client::new(&env, &license.terms.lien).balance(&env.current_contract_address()),
-            1
-        );
+    fn pay_for(env: Env, property: Address, licensee: Address, payer: Address) -> License {
+        payer.require_auth();
+        pay_license(&env, property, &licensee, &payer)
+    }
 
-        agreement!(license.terms.compensation).pay(&env, &licensee, &mut license);
-        storage::set::<DataKey, License>(&env, &DataKey::License(property), &license);
-        license
+    fn quote(env: Env, property: Address, licensee: Address) -> Vec<(i128, Address)> {
+        match Self::get_license(env.clone(), property) {
+            Some(license) => {
+                agreement!(license.terms.compensation).quote(&env, &licensee, &license)
+            }
+            None => Vec::new(&env),
+        }
+    }
+
+    fn get_license(env: Env, property: Address) -> Option<License> {
+        License::load(&env, &property)
     }
 
     fn add_property(env: Env, terms: Terms) {
         terms.licensor.require_auth();
+        RoyaltyCircuit.require_closed(&env);
 
         let property = terms.property.clone();
         assert!(terms.recur_period > terms.grace_period || terms.recur_period == 0);
-        assert!(!storage::has::<DataKey, License>(
-            &env,
-            &DataKey::License(property.clone())
-        ));
+        assert!(
+            terms.compensation != Compensation::Percentage
+                || (0..=MAX_BPS).contains(&terms.royalty_interest)
+        );
+        assert!(terms.recipients.len() <= MAX_RECIPIENTS);
+        assert!(
+            terms.recipients.is_empty()
+                || terms.recipients.iter().map(|r| r.share).sum::<u32>() == MAX_BPS as u32
+        );
+        assert!(License::load(&env, &property).is_none());
         assert_eq!(
             token::Client::new(&env, &property).balance(&terms.licensor),
             1
@@ -182,28 +404,34 @@ impl RoyaltyInterface for RoyaltyContract {
             LicenseStatus::Paid,
             false,
         );
-        storage::set::<DataKey, License>(&env, &DataKey::License(property), &license);
+        // Pauses prior to the license do not extend its grace period.
+        env.storage().persistent().set::<DataKey, u64>(
+            &DataKey::PauseOffset(property.clone()),
+            &paused_duration(&env),
+        );
+        license.store(&env);
     }
 }
 
 #[contractimpl]
 impl RoyaltyContract {
+    // Commission rate in basis points (e.g., 250 for 2.5%).
     pub fn initialize(env: Env, admin: Address, commission_rate: i128) {
         assert!(!storage::has::<AdminDataKey, AdminData>(
             &env,
             &AdminDataKey::Root
         ));
-        storage::set::<AdminDataKey, AdminData>(
-            &env,
-            &AdminDataKey::Root,
-            &AdminData {
-                admin,
-                commission_rate,
-            },
-        );
+        AdminData {
+            admin,
+            commission_rate: commission_rate.clamp(0, MAX_BPS),
+        }
+        .store(&env);
     }
+}
 
-    #[cfg(test)]
+#[cfg(test)]
+#[contractimpl]
+impl RoyaltyContract {
     pub fn test_oracle_feed(env: Env, topic: Address, price: i128, asset: Address) {
         storage::set::<MarketDataKey, MarketData>(
             &env,
@@ -213,6 +441,25 @@ impl RoyaltyContract {
     }
 }
 
+fn pay_license(env: &Env, property: Address, licensee: &Address, payer: &Address) -> License {
+    RoyaltyCircuit.require_closed(env);
+    extend_instance_ttl(env);
+
+    let mut license = License::load(env, &property).unwrap();
+    assert_eq!(
+        token::Client::new(env, &license.terms.property).balance(licensee),
+        1
+    );
+    assert_eq!(
+        token::Client::new(env, &license.terms.lien).balance(&env.current_contract_address()),
+        1
+    );
+
+    agreement!(license.terms.compensation).pay(env, payer, licensee, &mut license);
+    license.store(env);
+    license
+}
+
 fn update_broker_whitelist(env: &Env, broker: &Address, remove: bool) {
     match remove {
         true => env
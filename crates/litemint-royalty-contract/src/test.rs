@@ -7,15 +7,19 @@
 */
 
 use crate::{
-    types::{Compensation, LicenseStatus, MarketData, Terms},
+    events::{CommissionRateUpdated, RoleUpdated, EVENTS_VERSION},
+    types::{
+        AdminData, Compensation, DataKey, LegacyLicense, LegacyTerms, License, LicenseStatus,
+        MarketData, Recipient, Role, Terms,
+    },
     RoyaltyContract, RoyaltyContractClient,
 };
 extern crate std;
 
 use core::panic::AssertUnwindSafe;
 use soroban_sdk::{
-    testutils::{Address as _, Logs},
-    token, Address, Env,
+    testutils::{Address as _, Events, Ledger, Logs},
+    token, vec, Address, Env, TryFromVal,
 };
 use std::{panic::catch_unwind, println};
 use token::{Client as TokenClient, StellarAssetClient as TokenAdminClient};
@@ -28,7 +32,7 @@ fn create_token_contract<'a>(e: &Env, admin: &Address) -> (TokenClient<'a>, Toke
     )
 }
 
-fn create_royalty_contract(e: &Env) -> RoyaltyContractClient {
+fn create_royalty_contract(e: &Env) -> RoyaltyContractClient<'_> {
     RoyaltyContractClient::new(e, &e.register_contract(None, RoyaltyContract {}))
 }
 
@@ -38,7 +42,7 @@ fn test_compensation_fixed_royalties() {
     env.mock_all_auths();
 
     let royalty_interest = 188; // Licensor: 183 Fixed
-    let commission_rate = 3; // Admin: 3%
+    let commission_rate = 300; // Admin: 3% (basis points)
     let transfer_fee = 100;
     let admin = Address::generate(&env);
     let licensor = Address::generate(&env);
@@ -64,6 +68,7 @@ fn test_compensation_fixed_royalties() {
         transfer_fee,
         recur_period: 0,
         grace_period: 60,
+        recipients: vec![&env],
     };
 
     let royalty_contract = create_royalty_contract(&env);
@@ -111,15 +116,15 @@ fn test_compensation_fixed_royalties() {
     // Check balances.
     let admin_share_fixed = royalty_interest
         .checked_mul(commission_rate)
-        .and_then(|val| val.checked_add(99))
-        .and_then(|val| val.checked_div(100))
+        .and_then(|val| val.checked_add(9_999))
+        .and_then(|val| val.checked_div(10_000))
         .unwrap()
         .max(1);
 
     let admin_share_transfer = transfer_fee
         .checked_mul(commission_rate)
-        .and_then(|val| val.checked_add(99))
-        .and_then(|val| val.checked_div(100))
+        .and_then(|val| val.checked_add(9_999))
+        .and_then(|val| val.checked_div(10_000))
         .unwrap()
         .max(1);
 
@@ -129,7 +134,7 @@ fn test_compensation_fixed_royalties() {
         (royalty_interest - admin_share_fixed) + (transfer_fee - admin_share_transfer)
     );
     assert_eq!(
-        market.balance(&royalty_contract.address),
+        market.balance(&admin),
         admin_share_fixed + admin_share_transfer
     );
     assert_eq!(lien.balance(&royalty_contract.address), 1i128);
@@ -145,7 +150,7 @@ fn test_compensation_subscription_royalties() {
     env.mock_all_auths();
 
     let royalty_interest = 100;
-    let commission_rate = 3;
+    let commission_rate = 300;
     let transfer_fee = 100;
     let admin = Address::generate(&env);
     let licensor = Address::generate(&env);
@@ -171,6 +176,7 @@ fn test_compensation_subscription_royalties() {
         currency: market.address.clone(),
         recur_period: 706,
         grace_period: 60,
+        recipients: vec![&env],
     };
 
     let royalty_contract = create_royalty_contract(&env);
@@ -197,15 +203,15 @@ fn test_compensation_subscription_royalties() {
 
     let admin_share_fixed = royalty_interest
         .checked_mul(commission_rate)
-        .and_then(|val| val.checked_add(99))
-        .and_then(|val| val.checked_div(100))
+        .and_then(|val| val.checked_add(9_999))
+        .and_then(|val| val.checked_div(10_000))
         .unwrap()
         .max(1);
 
     let admin_share_transfer = transfer_fee
         .checked_mul(commission_rate)
-        .and_then(|val| val.checked_add(99))
-        .and_then(|val| val.checked_div(100))
+        .and_then(|val| val.checked_add(9_999))
+        .and_then(|val| val.checked_div(10_000))
         .unwrap()
         .max(1);
 
@@ -215,7 +221,7 @@ fn test_compensation_subscription_royalties() {
         royalty_interest * 2 - admin_share_transfer * 2 + (transfer_fee - admin_share_transfer)
     );
     assert_eq!(
-        market.balance(&royalty_contract.address),
+        market.balance(&admin),
         admin_share_transfer + admin_share_fixed * 2
     );
 
@@ -229,8 +235,8 @@ fn test_compensation_percentage_royalties() {
     let env = Env::default();
     env.mock_all_auths();
 
-    let royalty_interest = 10;
-    let commission_rate = 3;
+    let royalty_interest = 1000; // 10% (basis points)
+    let commission_rate = 300;
     let transfer_fee = 100;
     let admin = Address::generate(&env);
     let licensor = Address::generate(&env);
@@ -251,8 +257,8 @@ fn test_compensation_percentage_royalties() {
     let royalties_to_pay = market_data
         .price
         .checked_mul(royalty_interest)
-        .and_then(|val| val.checked_add(99))
-        .and_then(|val| val.checked_div(100))
+        .and_then(|val| val.checked_add(9_999))
+        .and_then(|val| val.checked_div(10_000))
         .unwrap();
     market_client.mint(&licensee, &(royalties_to_pay + transfer_fee));
 
@@ -267,6 +273,7 @@ fn test_compensation_percentage_royalties() {
         currency: market.address.clone(),
         recur_period: 0,
         grace_period: 60,
+        recipients: vec![&env],
     };
 
     let royalty_contract = create_royalty_contract(&env);
@@ -287,15 +294,15 @@ fn test_compensation_percentage_royalties() {
 
     let admin_share_percent = royalties_to_pay
         .checked_mul(commission_rate)
-        .and_then(|val| val.checked_add(99))
-        .and_then(|val| val.checked_div(100))
+        .and_then(|val| val.checked_add(9_999))
+        .and_then(|val| val.checked_div(10_000))
         .unwrap()
         .max(1);
 
     let admin_share_transfer = transfer_fee
         .checked_mul(commission_rate)
-        .and_then(|val| val.checked_add(99))
-        .and_then(|val| val.checked_div(100))
+        .and_then(|val| val.checked_add(9_999))
+        .and_then(|val| val.checked_div(10_000))
         .unwrap()
         .max(1);
 
@@ -304,7 +311,7 @@ fn test_compensation_percentage_royalties() {
         royalties_to_pay - admin_share_percent + transfer_fee - admin_share_transfer
     );
     assert_eq!(
-        market.balance(&royalty_contract.address),
+        market.balance(&admin),
         admin_share_percent + admin_share_transfer
     );
 
@@ -319,7 +326,7 @@ fn test_payment_enforcement() {
     env.mock_all_auths();
 
     let royalty_interest = 100;
-    let commission_rate = 3;
+    let commission_rate = 300;
     let admin = Address::generate(&env);
     let licensor = Address::generate(&env);
     let licensee = Address::generate(&env);
@@ -344,6 +351,7 @@ fn test_payment_enforcement() {
         currency: market.address.clone(),
         recur_period: 0,
         grace_period: 1473, // LATE
+        recipients: vec![&env],
     };
 
     let royalty_contract = create_royalty_contract(&env);
@@ -370,3 +378,475 @@ fn test_payment_enforcement() {
     assert_eq!(license.licensee, licensor);
     assert_eq!(lien.balance(&licensor), 1i128);
 }
+
+#[test]
+fn test_pausable_contract() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let royalty_interest = 100;
+    let commission_rate = 300;
+    let admin = Address::generate(&env);
+    let licensor = Address::generate(&env);
+    let licensee = Address::generate(&env);
+    let nft_issuer = Address::generate(&env);
+
+    let (property, property_client) = create_token_contract(&env, &nft_issuer);
+    let (lien, lien_client) = create_token_contract(&env, &nft_issuer);
+    let (market, market_client) = create_token_contract(&env, &admin);
+
+    property_client.mint(&licensor, &1);
+    lien_client.mint(&licensor, &1);
+    market_client.mint(&licensee, &royalty_interest);
+
+    // Create the terms for royalties with a fixed compensation model.
+    let terms = Terms {
+        licensor: licensor.clone(),
+        property: property.address.clone(),
+        lien: lien.address.clone(),
+        compensation: Compensation::Fixed,
+        royalty_interest,
+        transfer_fee: 0,
+        currency: market.address.clone(),
+        recur_period: 0,
+        grace_period: 1473, // LATE
+        recipients: vec![&env],
+    };
+
+    let royalty_contract = create_royalty_contract(&env);
+    royalty_contract.initialize(&admin, &commission_rate);
+    royalty_contract.add_property(&terms);
+    royalty_contract.execute(&terms.property);
+
+    // Executing or paying should panic while paused.
+    royalty_contract.pause();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        royalty_contract.execute(&terms.property);
+    }));
+    assert!(result.is_err(), "Should panic. Contract is paused.");
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        royalty_contract.pay(&terms.property, &licensee);
+    }));
+    assert!(result.is_err(), "Should panic. Contract is paused.");
+    royalty_contract.unpause();
+
+    // Freeze the property and transfer NFT to licensee.
+    royalty_contract.freeze(&terms.property);
+    property.transfer(&licensor, &licensee, &1);
+    let mut license = royalty_contract.execute(&terms.property);
+    assert_eq!(license.status, LicenseStatus::Unpaid);
+
+    // Grace period would expire on that call (test value 1473)
+    // but the license cannot be breached while frozen.
+    license = royalty_contract.execute(&terms.property);
+    assert_eq!(license.status, LicenseStatus::Unpaid);
+    assert_eq!(lien.balance(&royalty_contract.address), 1i128);
+
+    // Unfreezing resumes the grace period where it was suspended.
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    license = royalty_contract.unfreeze(&terms.property);
+    assert_eq!(license.grace_time, 100 + terms.grace_period);
+    license = royalty_contract.execute(&terms.property);
+    assert_eq!(license.status, LicenseStatus::Unpaid);
+
+    // Pausing the contract suspends the grace period as well.
+    royalty_contract.pause();
+    env.ledger().with_mut(|li| li.timestamp = 400);
+    royalty_contract.unpause();
+    license = royalty_contract.execute(&terms.property);
+    assert_eq!(license.status, LicenseStatus::Unpaid);
+    assert_eq!(license.grace_time, 400 + terms.grace_period);
+
+    // Make the royalty payment.
+    license = royalty_contract.pay(&terms.property, &licensee);
+    assert_eq!(license.status, LicenseStatus::Paid);
+    assert_eq!(license.licensee, licensee);
+    assert_eq!(market.balance(&licensee), 0);
+}
+
+#[test]
+fn test_split_royalties() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let royalty_interest = 1001;
+    let commission_rate = 0;
+    let admin = Address::generate(&env);
+    let licensor = Address::generate(&env);
+    let licensee = Address::generate(&env);
+    let nft_issuer = Address::generate(&env);
+    let recipients = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+
+    let (property, property_client) = create_token_contract(&env, &nft_issuer);
+    let (lien, lien_client) = create_token_contract(&env, &nft_issuer);
+    let (market, market_client) = create_token_contract(&env, &admin);
+
+    property_client.mint(&licensor, &1);
+    lien_client.mint(&licensor, &1);
+    market_client.mint(&licensee, &royalty_interest);
+
+    // Artist 50%, musician 30% and studio 20%.
+    let mut terms = Terms {
+        licensor: licensor.clone(),
+        property: property.address.clone(),
+        lien: lien.address.clone(),
+        compensation: Compensation::Fixed,
+        royalty_interest,
+        currency: market.address.clone(),
+        transfer_fee: 0,
+        recur_period: 0,
+        grace_period: 60,
+        recipients: vec![
+            &env,
+            Recipient { address: recipients[0].clone(), share: 5000 },
+            Recipient { address: recipients[1].clone(), share: 3000 },
+            Recipient { address: recipients[2].clone(), share: 1000 },
+        ],
+    };
+
+    let royalty_contract = create_royalty_contract(&env);
+    royalty_contract.initialize(&admin, &commission_rate);
+
+    // Shares must total 10000 basis points.
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        royalty_contract.add_property(&terms);
+    }));
+    assert!(result.is_err(), "Should panic. Invalid shares.");
+
+    terms.recipients.set(2, Recipient { address: recipients[2].clone(), share: 2000 });
+    royalty_contract.add_property(&terms);
+
+    // Transfer and pay, shares are rounded down with remainder to first recipient.
+    property.transfer(&licensor, &licensee, &1);
+    royalty_contract.pay(&property.address, &licensee);
+    assert_eq!(market.balance(&recipients[0]), 501);
+    assert_eq!(market.balance(&recipients[1]), 300);
+    assert_eq!(market.balance(&recipients[2]), 200);
+    assert_eq!(market.balance(&licensor), 0);
+    assert_eq!(market.balance(&licensee), 0);
+    assert_eq!(market.balance(&royalty_contract.address), 0);
+
+    // Legacy licenses (single licensor, no recipients) pay the licensor in full.
+    let (legacy_property, legacy_property_client) = create_token_contract(&env, &nft_issuer);
+    let (legacy_lien, legacy_lien_client) = create_token_contract(&env, &nft_issuer);
+    legacy_property_client.mint(&licensee, &1);
+    legacy_lien_client.mint(&royalty_contract.address, &1);
+    market_client.mint(&licensee, &100);
+    env.as_contract(&royalty_contract.address, || {
+        env.storage().instance().set(
+            &DataKey::License(legacy_property.address.clone()),
+            &LegacyLicense {
+                terms: LegacyTerms {
+                    licensor: licensor.clone(),
+                    property: legacy_property.address.clone(),
+                    lien: legacy_lien.address.clone(),
+                    compensation: Compensation::Fixed,
+                    royalty_interest: 100,
+                    transfer_fee: 0,
+                    currency: market.address.clone(),
+                    recur_period: 0,
+                    grace_period: 60,
+                },
+                licensee: licensor.clone(),
+                created_time: 0,
+                recur_time: 0,
+                grace_time: 60,
+                status: LicenseStatus::Paid,
+                transferring: false,
+            },
+        );
+    });
+    assert!(royalty_contract.get_license(&legacy_property.address).unwrap().terms.recipients.is_empty());
+    royalty_contract.pay(&legacy_property.address, &licensee);
+    assert_eq!(market.balance(&licensor), 100);
+    assert_eq!(royalty_contract.get_license(&legacy_property.address).unwrap().licensee, licensee);
+}
+
+#[test]
+fn test_basis_point_rates() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let royalty_interest = 750; // 7.5%
+    let commission_rate = 250; // 2.5%
+    let price = 12345;
+    let admin = Address::generate(&env);
+    let licensor = Address::generate(&env);
+    let licensee = Address::generate(&env);
+    let nft_issuer = Address::generate(&env);
+    let recipients = [Address::generate(&env), Address::generate(&env), Address::generate(&env)];
+
+    let (property, property_client) = create_token_contract(&env, &nft_issuer);
+    let (lien, lien_client) = create_token_contract(&env, &nft_issuer);
+    let (market, market_client) = create_token_contract(&env, &admin);
+
+    property_client.mint(&licensor, &1);
+    lien_client.mint(&licensor, &1);
+    market_client.mint(&licensee, &10000);
+
+    let terms = Terms {
+        licensor: licensor.clone(),
+        property: property.address.clone(),
+        lien: lien.address.clone(),
+        compensation: Compensation::Percentage,
+        royalty_interest,
+        currency: market.address.clone(),
+        transfer_fee: 0,
+        recur_period: 0,
+        grace_period: 60,
+        recipients: vec![
+            &env,
+            Recipient { address: recipients[0].clone(), share: 3333 },
+            Recipient { address: recipients[1].clone(), share: 3333 },
+            Recipient { address: recipients[2].clone(), share: 3334 },
+        ],
+    };
+
+    let royalty_contract = create_royalty_contract(&env);
+    royalty_contract.initialize(&admin, &commission_rate);
+    royalty_contract.add_property(&terms);
+    royalty_contract.test_oracle_feed(&property.address, &price, &market.address);
+
+    // Royalty and commission are rounded up, the shares always add up to the amount paid.
+    let royalty = (price * royalty_interest + 9_999) / 10_000;
+    let commission = (royalty * commission_rate + 9_999) / 10_000;
+    assert_eq!(royalty_contract.quote(&property.address, &licensee).get_unchecked(0).0, royalty);
+    property.transfer(&licensor, &licensee, &1);
+    royalty_contract.pay(&property.address, &licensee);
+    assert_eq!(market.balance(&licensee), 10000 - royalty);
+    assert_eq!(market.balance(&admin), commission);
+    assert_eq!(
+        recipients.iter().map(|r| market.balance(r)).sum::<i128>() + commission,
+        royalty
+    );
+    assert_eq!(market.balance(&recipients[1]), (royalty - commission) * 3333 / 10_000);
+
+    // Legacy records (baseline layout, rates in percent) are migrated to basis points.
+    env.as_contract(&royalty_contract.address, || {
+        let license = License::load(&env, &property.address).unwrap();
+        let legacy_license = LegacyLicense {
+            terms: LegacyTerms {
+                licensor: licensor.clone(),
+                property: property.address.clone(),
+                lien: lien.address.clone(),
+                compensation: Compensation::Percentage,
+                royalty_interest: 7,
+                transfer_fee: 0,
+                currency: market.address.clone(),
+                recur_period: 0,
+                grace_period: 60,
+            },
+            licensee: license.licensee,
+            created_time: license.created_time,
+            recur_time: license.recur_time,
+            grace_time: license.grace_time,
+            status: license.status,
+            transferring: license.transferring,
+        };
+        env.storage().persistent().remove(&DataKey::License(property.address.clone()));
+        env.storage().persistent().remove(&DataKey::LicenseVersion(property.address.clone()));
+        env.storage().instance().set(&DataKey::License(property.address.clone()), &legacy_license);
+        assert_eq!(License::load(&env, &property.address).unwrap().terms.royalty_interest, 700);
+
+        let mut admin_data = AdminData::load(&env);
+        admin_data.commission_rate = 3;
+        admin_data.store(&env);
+        env.storage().instance().remove(&DataKey::StorageVersion);
+        assert_eq!(AdminData::load(&env).commission_rate, 300);
+        assert_eq!(AdminData::load(&env).commission_rate, 300);
+    });
+    let license = royalty_contract.execute(&property.address);
+    assert_eq!(license.terms.royalty_interest, 700);
+    assert_eq!(royalty_contract.get_license(&property.address).unwrap().terms.royalty_interest, 700);
+}
+
+#[test]
+fn test_roles() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let royalty_interest = 1000;
+    let commission_rate = 250; // 2.5%
+    let admin = Address::generate(&env);
+    let licensor = Address::generate(&env);
+    let licensee = Address::generate(&env);
+    let nft_issuer = Address::generate(&env);
+    let [operator, treasury, broker_manager, pauser] = [0; 4].map(|_| Address::generate(&env));
+
+    let (property, property_client) = create_token_contract(&env, &nft_issuer);
+    let (lien, lien_client) = create_token_contract(&env, &nft_issuer);
+    let (market, market_client) = create_token_contract(&env, &admin);
+
+    property_client.mint(&licensor, &1);
+    lien_client.mint(&licensor, &1);
+    market_client.mint(&licensee, &(royalty_interest * 2));
+
+    let terms = Terms {
+        licensor: licensor.clone(),
+        property: property.address.clone(),
+        lien: lien.address.clone(),
+        compensation: Compensation::Fixed,
+        royalty_interest,
+        transfer_fee: 0,
+        currency: market.address.clone(),
+        recur_period: 0,
+        grace_period: 60,
+        recipients: vec![&env],
+    };
+
+    let royalty_contract = create_royalty_contract(&env);
+    royalty_contract.initialize(&admin, &commission_rate);
+    royalty_contract.add_property(&terms);
+
+    // Roles are held by the admin until granted.
+    assert_eq!(royalty_contract.get_role(&Role::Pauser), admin);
+    assert_eq!(royalty_contract.get_role(&Role::Treasury), admin);
+    royalty_contract.pause();
+    assert_eq!(env.auths()[0].0, admin);
+    royalty_contract.unpause();
+
+    // Granting a role requires the admin.
+    for (role, account) in [
+        (Role::ConfigOperator, &operator),
+        (Role::Treasury, &treasury),
+        (Role::BrokerManager, &broker_manager),
+        (Role::Pauser, &pauser),
+    ] {
+        royalty_contract.grant_role(&role, account);
+        assert_eq!(env.auths()[0].0, admin);
+        assert_eq!(royalty_contract.get_role(&role), *account);
+    }
+    let granted = env.events().all().last().unwrap().2;
+    assert_eq!(
+        RoleUpdated::try_from_val(&env, &granted).unwrap(),
+        RoleUpdated {
+            version: EVENTS_VERSION,
+            role: Role::Pauser,
+            account: pauser.clone(),
+        }
+    );
+
+    // Privileged entry points require their role holder.
+    royalty_contract.pause();
+    assert_eq!(env.auths()[0].0, pauser);
+    royalty_contract.unpause();
+    royalty_contract.freeze(&terms.property);
+    assert_eq!(env.auths()[0].0, pauser);
+    royalty_contract.unfreeze(&terms.property);
+    assert_eq!(env.auths()[0].0, pauser);
+    royalty_contract.allow_broker(&Address::generate(&env));
+    assert_eq!(env.auths()[0].0, broker_manager);
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        royalty_contract.set_commission_rate(&10_001);
+    }));
+    assert!(result.is_err(), "Should panic. Invalid commission rate.");
+    royalty_contract.set_commission_rate(&500);
+    assert_eq!(env.auths()[0].0, operator);
+    let updated = env.events().all().last().unwrap().2;
+    assert_eq!(
+        CommissionRateUpdated::try_from_val(&env, &updated).unwrap(),
+        CommissionRateUpdated {
+            version: EVENTS_VERSION,
+            old_rate: commission_rate,
+            new_rate: 500,
+        }
+    );
+
+    // Commissions are paid to the treasury.
+    property.transfer(&licensor, &licensee, &1);
+    royalty_contract.pay(&terms.property, &licensee);
+    assert_eq!(market.balance(&treasury), 50);
+    assert_eq!(market.balance(&licensor), royalty_interest - 50);
+    assert_eq!(market.balance(&royalty_contract.address), 0);
+
+    // Revoked roles return to their default holder.
+    royalty_contract.revoke_role(&Role::Treasury);
+    assert_eq!(env.auths()[0].0, admin);
+    let revoked = env.events().all().last().unwrap().2;
+    assert_eq!(
+        RoleUpdated::try_from_val(&env, &revoked).unwrap(),
+        RoleUpdated {
+            version: EVENTS_VERSION,
+            role: Role::Treasury,
+            account: treasury.clone(),
+        }
+    );
+    assert_eq!(royalty_contract.get_role(&Role::Treasury), admin);
+    royalty_contract.revoke_role(&Role::Pauser);
+    royalty_contract.pause();
+    assert_eq!(env.auths()[0].0, admin);
+}
+
+#[test]
+fn test_storage_migration() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let licensor = Address::generate(&env);
+    let licensee = Address::generate(&env);
+    let nft_issuer = Address::generate(&env);
+
+    let (property, property_client) = create_token_contract(&env, &nft_issuer);
+    let (lien, lien_client) = create_token_contract(&env, &nft_issuer);
+    let (market, market_client) = create_token_contract(&env, &admin);
+
+    let royalty_contract = create_royalty_contract(&env);
+    royalty_contract.initialize(&admin, &0);
+
+    property_client.mint(&licensor, &1);
+    lien_client.mint(&royalty_contract.address, &1);
+    market_client.mint(&licensee, &100);
+
+    // License held by legacy versions, in instance storage with its original layout.
+    let legacy_license = LegacyLicense {
+        terms: LegacyTerms {
+            licensor: licensor.clone(),
+            property: property.address.clone(),
+            lien: lien.address.clone(),
+            compensation: Compensation::Fixed,
+            royalty_interest: 100,
+            transfer_fee: 0,
+            currency: market.address.clone(),
+            recur_period: 0,
+            grace_period: 60,
+        },
+        licensee: licensor.clone(),
+        created_time: 0,
+        recur_time: 0,
+        grace_time: 60,
+        status: LicenseStatus::Paid,
+        transferring: false,
+    };
+    let keys = [
+        DataKey::License(property.address.clone()),
+        DataKey::LicenseVersion(property.address.clone()),
+    ];
+    env.as_contract(&royalty_contract.address, || {
+        env.storage().instance().set(&keys[0], &legacy_license);
+    });
+
+    // Legacy licenses are converted and moved by the upgrader (or on first access).
+    royalty_contract.migrate(&vec![&env, property.address.clone(), Address::generate(&env)]);
+    assert_eq!(env.auths()[0].0, admin);
+    env.as_contract(&royalty_contract.address, || {
+        for key in keys.iter() {
+            assert!(env.storage().persistent().has(key));
+            assert!(!env.storage().instance().has(key));
+        }
+        let license = env.storage().persistent().get::<DataKey, License>(&keys[0]).unwrap();
+        assert_eq!(license.terms.licensor, licensor);
+        assert!(license.terms.recipients.is_empty());
+        assert_eq!(env.storage().persistent().get::<DataKey, u32>(&keys[1]), Some(1));
+    });
+
+    // The migrated license is enforced as before.
+    property.transfer(&licensor, &licensee, &1);
+    env.ledger().with_mut(|li| li.timestamp = 100);
+    let mut license = royalty_contract.execute(&property.address);
+    assert_eq!(license.status, LicenseStatus::Unpaid);
+    assert_eq!(license.grace_time, 100 + 60);
+    license = royalty_contract.pay(&property.address, &licensee);
+    assert_eq!(license.status, LicenseStatus::Paid);
+    assert_eq!(market.balance(&licensor), 100);
+}
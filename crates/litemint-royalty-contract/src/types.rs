@@ -7,7 +7,53 @@
 */
 
 use soroban_kit::{key_constraint, soroban_tools, storage};
-use soroban_sdk::{contracttype, Address, Env};
+use soroban_sdk::{contracttype, Address, Env, IntoVal, Val, Vec};
+
+use ledger_times::convert_seconds_to_ledgers;
+
+// Rates and shares are expressed in basis points.
+pub const MAX_BPS: i128 = 10_000;
+
+// Storage version, rates were expressed in percent before version 1.
+pub(crate) const STORAGE_VERSION: u32 = 1;
+
+// Same ledger times as the auction contract.
+pub mod ledger_times {
+    // Assuming 6 seconds average time per ledger.
+    pub const SECONDS_PER_LEDGER: u64 = 6;
+    pub const LEDGERS_PER_MINUTE: u64 = 60 / SECONDS_PER_LEDGER;
+    pub const LEDGERS_PER_HOUR: u64 = LEDGERS_PER_MINUTE * 60;
+    pub const LEDGERS_PER_DAY: u64 = LEDGERS_PER_HOUR * 24;
+    pub const LEDGERS_PER_YEAR: u64 = LEDGERS_PER_DAY * 365;
+
+    // Number of ledgers covering `seconds` (i.e., seconds / 6 rounded up), capped to a year.
+    pub const fn convert_seconds_to_ledgers(seconds: u64) -> u32 {
+        let ledgers = seconds.div_ceil(SECONDS_PER_LEDGER);
+        if ledgers < LEDGERS_PER_YEAR {
+            ledgers as u32
+        } else {
+            LEDGERS_PER_YEAR as u32
+        }
+    }
+}
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+// Licenses time to live, extended to about 180 days whenever a license is accessed
+// with less than 30 days left.
+pub(crate) const LICENSE_TTL: u32 = convert_seconds_to_ledgers(180 * SECONDS_PER_DAY);
+pub(crate) const LICENSE_TTL_THRESHOLD: u32 = convert_seconds_to_ledgers(30 * SECONDS_PER_DAY);
+
+// Contract instance time to live, extended by payments in the same way as licenses.
+const INSTANCE_TTL: u32 = LICENSE_TTL;
+const INSTANCE_TTL_THRESHOLD: u32 = LICENSE_TTL_THRESHOLD;
+
+// Extend the time to live of the contract instance (admin data, configuration and roles).
+pub(crate) fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_TTL_THRESHOLD, INSTANCE_TTL);
+}
 
 #[derive(Clone)]
 #[contracttype]
@@ -15,6 +61,25 @@ use soroban_sdk::{contracttype, Address, Env};
 pub(crate) enum DataKey {
     License(Address),
     BrokerWhitelist(Address),
+    Frozen(Address),
+    StorageVersion,
+    LicenseVersion(Address),
+    Role(Role),
+    PausedTime,
+    PausedDuration,
+    PauseOffset(Address),
+}
+
+// Total time (in seconds) the contract has spent paused, including the current pause.
+pub(crate) fn paused_duration(env: &Env) -> u64 {
+    let instance = env.storage().instance();
+    let paused_duration = instance
+        .get::<DataKey, u64>(&DataKey::PausedDuration)
+        .unwrap_or(0);
+    match instance.get::<DataKey, u64>(&DataKey::PausedTime) {
+        Some(paused_time) => paused_duration + (env.ledger().timestamp() - paused_time),
+        None => paused_duration,
+    }
 }
 
 #[contracttype]
@@ -35,6 +100,13 @@ impl Compensation {
     }
 }
 
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Recipient {
+    pub address: Address,
+    pub share: u32, // Basis points.
+}
+
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct Terms {
@@ -47,6 +119,7 @@ pub struct Terms {
     pub currency: Address,
     pub recur_period: u64,
     pub grace_period: u64,
+    pub recipients: Vec<Recipient>,
 }
 
 #[contracttype]
@@ -58,7 +131,7 @@ pub enum LicenseStatus {
 }
 
 #[contracttype]
-#[storage(Instance, DataKeyConstraint)]
+#[storage(Persistent, DataKeyConstraint)]
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct License {
     pub terms: Terms,
@@ -90,6 +163,153 @@ impl License {
             transferring,
         }
     }
+
+    // Load the license for a property, extending its time to live.
+    // Legacy records (see `LegacyLicense`) are converted, with percentage royalty migrated
+    // to basis points, and moved from instance to persistent storage.
+    pub fn load(env: &Env, property: &Address) -> Option<Self> {
+        let key = DataKey::License(property.clone());
+        let version_key = DataKey::LicenseVersion(property.clone());
+        if !env.storage().persistent().has::<DataKey>(&key) {
+            if !env.storage().instance().has::<DataKey>(&key) {
+                return None;
+            }
+            migrate_entry(env, &key);
+            migrate_entry(env, &version_key);
+            migrate_entry(env, &DataKey::Frozen(property.clone()));
+        }
+        Self::extend_ttl(env, property);
+
+        // Legacy records are decoded with their original layout and stored converted.
+        let version = env
+            .storage()
+            .persistent()
+            .get::<DataKey, u32>(&version_key)
+            .unwrap_or(0);
+        if version < STORAGE_VERSION {
+            let license = env
+                .storage()
+                .persistent()
+                .get::<DataKey, LegacyLicense>(&key)?
+                .into_license(env);
+            license.store(env);
+            return Some(license);
+        }
+        storage::get::<DataKey, License>(env, &key)
+    }
+
+    // Shift the grace period by the time the contract spent paused since the last shift.
+    // Pauses overlapping a freeze are skipped when `frozen`, as unfreezing already covers them.
+    pub fn shift_grace_time(&mut self, env: &Env, frozen: bool) {
+        let key = DataKey::PauseOffset(self.terms.property.clone());
+        let paused_duration = paused_duration(env);
+        let offset = env
+            .storage()
+            .persistent()
+            .get::<DataKey, u64>(&key)
+            .unwrap_or(0);
+        if paused_duration > offset {
+            if !frozen && self.status == LicenseStatus::Unpaid {
+                self.grace_time += paused_duration - offset;
+            }
+            env.storage()
+                .persistent()
+                .set::<DataKey, u64>(&key, &paused_duration);
+        }
+    }
+
+    // Store the license, stamped with the current storage version.
+    pub fn store(&self, env: &Env) {
+        let property = &self.terms.property;
+        storage::set::<DataKey, License>(env, &DataKey::License(property.clone()), self);
+        env.storage()
+            .persistent()
+            .set::<DataKey, u32>(&DataKey::LicenseVersion(property.clone()), &STORAGE_VERSION);
+        Self::extend_ttl(env, property);
+    }
+
+    // Extend the time to live of the license entries (license, version, freeze and pause offset), see `LICENSE_TTL`.
+    pub fn extend_ttl(env: &Env, property: &Address) {
+        let persistent = env.storage().persistent();
+        for key in [
+            DataKey::License(property.clone()),
+            DataKey::LicenseVersion(property.clone()),
+            DataKey::Frozen(property.clone()),
+            DataKey::PauseOffset(property.clone()),
+        ] {
+            if persistent.has(&key) {
+                persistent.extend_ttl(&key, LICENSE_TTL_THRESHOLD, LICENSE_TTL);
+            }
+        }
+    }
+}
+
+// Layout of `Terms` before storage version 1 (single licensor, no recipients).
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct LegacyTerms {
+    pub licensor: Address,
+    pub property: Address,
+    pub lien: Address,
+    pub compensation: Compensation,
+    pub royalty_interest: i128,
+    pub transfer_fee: i128,
+    pub currency: Address,
+    pub recur_period: u64,
+    pub grace_period: u64,
+}
+
+// Layout of `License` before storage version 1.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct LegacyLicense {
+    pub terms: LegacyTerms,
+    pub licensee: Address,
+    pub created_time: u64,
+    pub recur_time: u64,
+    pub grace_time: u64,
+    pub status: LicenseStatus,
+    pub transferring: bool,
+}
+
+impl LegacyLicense {
+    // Convert to the current layout, royalties are paid entirely to the licensor
+    // and percentage rates are converted to basis points.
+    pub fn into_license(self, env: &Env) -> License {
+        let terms = self.terms;
+        let royalty_interest = match terms.compensation {
+            Compensation::Percentage => terms.royalty_interest.checked_mul(100).unwrap(),
+            _ => terms.royalty_interest,
+        };
+        License::new(
+            Terms {
+                licensor: terms.licensor,
+                property: terms.property,
+                lien: terms.lien,
+                compensation: terms.compensation,
+                royalty_interest,
+                transfer_fee: terms.transfer_fee,
+                currency: terms.currency,
+                recur_period: terms.recur_period,
+                grace_period: terms.grace_period,
+                recipients: Vec::new(env),
+            },
+            self.licensee,
+            self.created_time,
+            self.recur_time,
+            self.grace_time,
+            self.status,
+            self.transferring,
+        )
+    }
+}
+
+// Move an entry from instance to persistent storage, if any.
+fn migrate_entry<K: IntoVal<Env, Val>>(env: &Env, key: &K) {
+    if let Some(value) = env.storage().instance().get::<K, Val>(key) {
+        env.storage().persistent().set::<K, Val>(key, &value);
+        env.storage().instance().remove::<K>(key);
+    }
 }
 
 #[derive(Clone)]
@@ -107,6 +327,60 @@ pub(crate) struct AdminData {
     pub commission_rate: i128,
 }
 
+impl AdminData {
+    // Load the admin data.
+    // Legacy records (commission rate in percent) are migrated to basis points.
+    pub fn load(env: &Env) -> Self {
+        let mut admin_data =
+            storage::get::<AdminDataKey, AdminData>(env, &AdminDataKey::Root).unwrap();
+        if env
+            .storage()
+            .instance()
+            .get::<DataKey, u32>(&DataKey::StorageVersion)
+            .unwrap_or(0)
+            < STORAGE_VERSION
+        {
+            admin_data.commission_rate = admin_data.commission_rate.checked_mul(100).unwrap();
+            admin_data.store(env);
+        }
+        admin_data
+    }
+
+    // Store the admin data, stamped with the current storage version.
+    pub fn store(&self, env: &Env) {
+        storage::set::<AdminDataKey, AdminData>(env, &AdminDataKey::Root, self);
+        env.storage()
+            .instance()
+            .set::<DataKey, u32>(&DataKey::StorageVersion, &STORAGE_VERSION);
+    }
+}
+
+// Privileged roles, held by the admin unless granted to another account.
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Role {
+    Upgrader,       // Contract upgrades.
+    ConfigOperator, // Commission rate.
+    Treasury,       // Recipient of commissions.
+    BrokerManager,  // Oracle broker whitelist.
+    Pauser,         // Circuit breaker and property freeze.
+}
+
+impl Role {
+    // Account holding the role, the admin if not granted.
+    pub(crate) fn holder(&self, env: &Env) -> Address {
+        env.storage()
+            .instance()
+            .get::<DataKey, Address>(&DataKey::Role(*self))
+            .unwrap_or_else(|| AdminData::load(env).admin)
+    }
+
+    // Require authorization from the role holder.
+    pub(crate) fn require_auth(&self, env: &Env) {
+        self.holder(env).require_auth();
+    }
+}
+
 #[contracttype]
 #[key_constraint(MarketDataKeyConstraint)]
 #[derive(Clone, Debug, Eq, PartialEq)]